@@ -1 +1,87 @@
+use crate::core::rules::Rules;
+use crate::error::{ConfigError, ConfigResult};
+use serde::{Deserialize, Serialize};
 
+/// Table settings as written by the host. A named preset supplies the rules, and each key in a
+/// `rules` table replaces the preset's value for that field whole.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TableConfig {
+    pub preset: Option<String>,
+    pub rules: Option<toml::Table>,
+}
+
+impl TableConfig {
+    pub fn from_toml(text: &str) -> ConfigResult<Self> {
+        toml::from_str(text).map_err(|e| ConfigError::Other(e.to_string()))
+    }
+
+    pub fn rules(&self) -> ConfigResult<Rules> {
+        let base = match &self.preset {
+            Some(name) => Rules::preset(name)?,
+            None => Rules::new(),
+        };
+        let Some(overrides) = &self.rules else {
+            return Ok(base);
+        };
+
+        let mut table =
+            toml::Table::try_from(base).map_err(|e| ConfigError::Other(e.to_string()))?;
+        table.extend(overrides.clone());
+        table
+            .try_into()
+            .map_err(|e| ConfigError::Other(format!("rules: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::rules::ShuffleMode;
+
+    #[test]
+    fn preset_by_name() {
+        let config = TableConfig::from_toml(r#"preset = "downtown""#).unwrap();
+        assert_eq!(config.rules().unwrap().num_decks, 2);
+    }
+
+    #[test]
+    fn unknown_preset_is_a_config_error() {
+        let config = TableConfig::from_toml(r#"preset = "monte carlo""#).unwrap();
+        assert!(matches!(config.rules(), Err(ConfigError::UnknownPreset(_))));
+    }
+
+    #[test]
+    fn rules_override_the_preset_key_by_key() {
+        let config = TableConfig::from_toml(
+            r#"
+            preset = "downtown"
+            [rules]
+            num_decks = 1
+            shuffle = { Continuous = { buffer = 10 } }
+            "#,
+        )
+        .unwrap();
+        let rules = config.rules().unwrap();
+        assert_eq!(rules.num_decks, 1);
+        assert_eq!(rules.shuffle, ShuffleMode::Continuous { buffer: 10 });
+        assert!(rules.dealer_hits_soft_17);
+        assert_eq!(rules.split_limit, 3);
+
+        let typo = TableConfig::from_toml("[rules]\nnum_decks = \"two\"").unwrap();
+        assert!(matches!(typo.rules(), Err(ConfigError::Other(_))));
+    }
+
+    #[test]
+    fn older_rules_fill_in_new_fields() {
+        let rules: Rules = toml::from_str("num_decks = 4\nmin_bet = 25").unwrap();
+        assert_eq!(rules.num_decks, 4);
+        assert_eq!(rules.min_bet, 25);
+        assert_eq!(rules.max_bet, Rules::new().max_bet);
+    }
+
+    #[test]
+    fn no_preset_means_default_rules() {
+        let config = TableConfig::from_toml("").unwrap();
+        assert_eq!(config.rules().unwrap().num_decks, Rules::new().num_decks);
+    }
+}
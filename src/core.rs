@@ -1,6 +1,12 @@
-mod card;
+pub mod card;
 pub mod hand;
-mod payout;
-mod rules;
-mod shoe;
+pub mod house_edge;
+pub mod payout;
+pub mod preset;
+pub mod progressive;
+pub mod ranking;
+pub mod rules;
+pub mod shoe;
+pub mod side_bet;
+pub mod split;
 mod tests;
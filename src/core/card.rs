@@ -12,6 +12,10 @@ impl Suit {
     pub const fn all() -> [Suit; 4] {
         [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
     }
+
+    pub const fn is_red(self) -> bool {
+        matches!(self, Suit::Diamonds | Suit::Hearts)
+    }
 }
 
 impl std::fmt::Display for Suit {
@@ -104,11 +108,41 @@ impl Card {
         deck
     }
 
+    /// Spanish 21 deck: the standard deck with its four tens taken out (picture cards stay).
+    pub fn spanish_deck() -> Vec<Self> {
+        let mut deck = Self::standard_deck();
+        deck.retain(|c| c.rank != Rank::Ten);
+        deck
+    }
+
     pub const fn pip_value(&self) -> u8 {
         self.rank.pip_value()
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeckKind {
+    #[default]
+    Standard,
+    Spanish, // 48 cards, no tens
+}
+
+impl DeckKind {
+    pub const fn size(self) -> usize {
+        match self {
+            DeckKind::Standard => 52,
+            DeckKind::Spanish => 48,
+        }
+    }
+
+    pub fn cards(self) -> Vec<Card> {
+        match self {
+            DeckKind::Standard => Card::standard_deck(),
+            DeckKind::Spanish => Card::spanish_deck(),
+        }
+    }
+}
+
 impl From<(Rank, Suit)> for Card {
     fn from((rank, suit): (Rank, Suit)) -> Self {
         Self::new(rank, suit)
@@ -149,4 +183,12 @@ mod tests {
             assert!(unique_cards.insert(card));
         }
     }
+
+    #[test]
+    fn spanish_deck_has_no_tens() {
+        let deck = DeckKind::Spanish.cards();
+        assert_eq!(deck.len(), DeckKind::Spanish.size());
+        assert!(deck.iter().all(|c| c.rank != Rank::Ten));
+        assert_eq!(deck.iter().filter(|c| c.pip_value() == 10).count(), 12);
+    }
 }
@@ -4,11 +4,19 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Hand {
     cards: Vec<Card>,
+    #[serde(default)]
+    split: bool,
+    #[serde(default)]
+    switched: bool,
 }
 
 impl Hand {
     pub fn new() -> Self {
-        Hand { cards: Vec::new() }
+        Hand {
+            cards: Vec::new(),
+            split: false,
+            switched: false,
+        }
     }
 
     pub fn add_card(&mut self, card: Card) {
@@ -37,8 +45,51 @@ impl Hand {
         total
     }
 
+    /// A natural: two-card 21 on an unsplit, unswitched hand. A 21 made after splitting or
+    /// switching is just 21.
     pub fn is_blackjack(&self) -> bool {
-        self.cards.len() == 2 && self.value() == 21
+        !self.split && !self.switched && self.cards.len() == 2 && self.value() == 21
+    }
+
+    /// Trades second cards with `other`, as Blackjack Switch allows between a seat's boxes.
+    /// Both hands are marked as switched afterwards.
+    pub fn swap_second_card(&mut self, other: &mut Hand) -> bool {
+        if self.cards.len() != 2 || other.cards.len() != 2 {
+            return false;
+        }
+        std::mem::swap(&mut self.cards[1], &mut other.cards[1]);
+        self.switched = true;
+        other.switched = true;
+        true
+    }
+
+    pub fn is_switched(&self) -> bool {
+        self.switched
+    }
+
+    pub fn is_split(&self) -> bool {
+        self.split
+    }
+
+    /// Two cards of equal value. Ten-valued cards pair with each other (K-Q splits).
+    pub fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].pip_value() == self.cards[1].pip_value()
+    }
+
+    /// Splits a pair into two one-card hands, returning the second. Both hands are marked as
+    /// split afterwards. Returns `None` if the hand is not a pair.
+    pub fn split_off(&mut self) -> Option<Hand> {
+        if !self.is_pair() {
+            return None;
+        }
+
+        let second = self.cards.pop()?;
+        self.split = true;
+        Some(Hand {
+            cards: vec![second],
+            split: true,
+            switched: self.switched,
+        })
     }
 
     pub fn is_bust(&self) -> bool {
@@ -148,6 +199,61 @@ mod tests {
         assert!(!hand.is_soft());
     }
 
+    #[test]
+    fn split_pair() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Rank::Eight, Suit::Spades));
+        hand.add_card(Card::new(Rank::Eight, Suit::Hearts));
+        assert!(hand.is_pair());
+
+        let other = hand.split_off().unwrap();
+        assert_eq!(hand.cards(), &[Card::new(Rank::Eight, Suit::Spades)]);
+        assert_eq!(other.cards(), &[Card::new(Rank::Eight, Suit::Hearts)]);
+        assert!(hand.is_split());
+        assert!(other.is_split());
+    }
+
+    #[test]
+    fn swap_second_cards() {
+        let mut a = Hand::new();
+        a.add_card(Card::new(Rank::Ten, Suit::Spades));
+        a.add_card(Card::new(Rank::Six, Suit::Hearts));
+        let mut b = Hand::new();
+        b.add_card(Card::new(Rank::Five, Suit::Clubs));
+        b.add_card(Card::new(Rank::Ace, Suit::Diamonds));
+
+        assert!(a.swap_second_card(&mut b));
+        assert_eq!(a.value(), 21);
+        assert!(!a.is_blackjack());
+        assert!(a.is_switched() && b.is_switched());
+        assert_eq!(b.value(), 11);
+
+        b.add_card(Card::new(Rank::Two, Suit::Clubs));
+        assert!(!a.swap_second_card(&mut b));
+    }
+
+    #[test]
+    fn split_non_pair() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Rank::Eight, Suit::Spades));
+        hand.add_card(Card::new(Rank::Nine, Suit::Hearts));
+        assert!(!hand.is_pair());
+        assert!(hand.split_off().is_none());
+        assert_eq!(hand.cards().len(), 2);
+    }
+
+    #[test]
+    fn split_21_is_not_blackjack() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Rank::Ace, Suit::Spades));
+        hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
+        hand.split_off().unwrap();
+
+        hand.add_card(Card::new(Rank::King, Suit::Clubs));
+        assert_eq!(hand.value(), 21);
+        assert!(!hand.is_blackjack());
+    }
+
     #[test]
     fn three_aces() {
         let mut hand = Hand::new();
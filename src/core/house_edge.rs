@@ -0,0 +1,166 @@
+//! Approximate house edge from the published table of rule effects (in percent of the
+//! initial bet), measured against single-deck, S17, no DAS, split to four hands, peek, 3:2.
+//! Good to a tenth of a percent or so; the exact engine is the place for anything finer.
+
+use crate::core::payout::PayoutRatio;
+use crate::core::rules::{HoleCardRule, Rules, SurrenderRule, Variant};
+
+/// Edge of the baseline game the effects are measured against.
+const BASELINE_EDGE: f64 = 0.0;
+
+/// How often the player is dealt a natural from a six-deck shoe; scales the payout effect.
+const NATURAL_FREQUENCY: f64 = 0.0453;
+
+/// One line of the breakdown: a rule and what it adds to (or takes off) the house edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleEffect {
+    pub rule: String,
+    pub percent: f64,
+}
+
+/// Rule-by-rule breakdown against the baseline. Rules at their baseline setting are left out.
+pub fn rule_effects(rules: &Rules) -> Vec<RuleEffect> {
+    let mut effects = Vec::new();
+    let mut add = |rule: String, percent: f64| {
+        if percent != 0.0 {
+            effects.push(RuleEffect { rule, percent });
+        }
+    };
+
+    let decks = match rules.num_decks {
+        0 | 1 => 0.0,
+        2 => 0.32,
+        3 => 0.43,
+        4 => 0.48,
+        5 => 0.52,
+        6 => 0.54,
+        7 => 0.56,
+        8 => 0.57,
+        _ => 0.60,
+    };
+    add(format!("{} decks", rules.num_decks), decks);
+
+    if rules.dealer_hits_soft_17 {
+        add("dealer hits soft 17".into(), 0.22);
+    }
+    if rules.double_after_split_allowed {
+        add("double after split".into(), -0.14);
+    }
+    match rules.split_limit {
+        0 => add("no splitting".into(), 0.57),
+        1 => add("split to two hands".into(), 0.10),
+        2 => add("split to three hands".into(), 0.03),
+        _ => {}
+    }
+    if rules.resplit_aces_allowed && rules.split_limit > 1 {
+        add("resplit aces".into(), -0.08);
+    }
+    if rules.hit_split_aces_allowed {
+        add("hit split aces".into(), -0.19);
+    }
+
+    match rules.surrender {
+        SurrenderRule::None => {}
+        SurrenderRule::Late => add("late surrender".into(), -0.08),
+        SurrenderRule::Early => add("early surrender".into(), -0.63),
+        SurrenderRule::EarlyExceptAce => add("early surrender except Ace".into(), -0.24),
+    }
+    // OBO loses the same money to a natural as peeking does.
+    if rules.hole_card == HoleCardRule::Enhc {
+        add("no hole card".into(), 0.11);
+    }
+
+    // Every tenth of the stake shaved off a natural costs the player about 0.45%.
+    if let Ok(ratio) = rules.blackjack_payout.ratio() {
+        let paid = ratio_value(ratio);
+        add(
+            format!("naturals pay {}:{}", ratio.numerator(), ratio.denominator()),
+            (1.5 - paid) * NATURAL_FREQUENCY * 100.0,
+        );
+    }
+
+    effects
+}
+
+/// Approximate house edge in percent; negative means the rules favour the player. Variants
+/// start from their published edge and only the rules changed from their preset are applied.
+pub fn house_edge(rules: &Rules) -> f64 {
+    let total = |rules: &Rules| rule_effects(rules).iter().map(|e| e.percent).sum::<f64>();
+    match variant_standard(rules.variant) {
+        Some((edge, standard)) => edge + total(rules) - total(&standard),
+        None => BASELINE_EDGE + total(rules),
+    }
+}
+
+// Published edges for each variant's standard game, alongside the rules that game uses.
+fn variant_standard(variant: Variant) -> Option<(f64, Rules)> {
+    match variant {
+        Variant::Classic => None,
+        Variant::Spanish21 => Some((0.76, Rules::spanish_21())),
+        Variant::Switch => Some((0.58, Rules::blackjack_switch())),
+        Variant::DoubleExposure => Some((0.69, Rules::double_exposure())),
+        Variant::FreeBet => Some((1.00, Rules::free_bet())),
+        Variant::Pontoon => Some((0.38, Rules::pontoon())),
+    }
+}
+
+fn ratio_value(ratio: PayoutRatio) -> f64 {
+    ratio.numerator() as f64 / ratio.denominator() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::payout::BlackjackPayout;
+    use crate::core::preset::Preset;
+
+    #[test]
+    fn six_deck_h17_close_to_published() {
+        let rules = Rules {
+            dealer_hits_soft_17: true,
+            split_limit: 3,
+            ..Rules::new()
+        };
+        assert!((house_edge(&rules) - 0.62).abs() < 0.01);
+    }
+
+    #[test]
+    fn six_to_five_costs_about_one_and_a_third() {
+        let fair = Preset::LasVegasStrip.rules();
+        let trap = Rules {
+            blackjack_payout: BlackjackPayout::Vegas,
+            ..fair.clone()
+        };
+        let cost = house_edge(&trap) - house_edge(&fair);
+        assert!((cost - 1.36).abs() < 0.01);
+    }
+
+    #[test]
+    fn presets_rank_sensibly() {
+        let edge = |p: Preset| house_edge(&p.rules());
+        assert!(edge(Preset::LasVegasStrip) < edge(Preset::AtlanticCity));
+        assert!(edge(Preset::Downtown) < edge(Preset::TouristTrap));
+        assert!(edge(Preset::TouristTrap) > 1.5);
+    }
+
+    #[test]
+    fn variant_standard_game_has_published_edge() {
+        assert!((house_edge(&Rules::spanish_21()) - 0.76).abs() < 1e-9);
+        let eight_deck_switch = Rules {
+            num_decks: 8,
+            ..Rules::blackjack_switch()
+        };
+        assert!(house_edge(&eight_deck_switch) > 0.58);
+    }
+
+    #[test]
+    fn baseline_rules_leave_no_lines() {
+        let rules = Rules {
+            num_decks: 1,
+            split_limit: 3,
+            double_after_split_allowed: false,
+            ..Rules::new()
+        };
+        assert!(rule_effects(&rules).is_empty());
+    }
+}
@@ -1,5 +1,8 @@
+use crate::core::card::{Card, Rank, Suit};
 use crate::core::hand::Hand;
-use crate::core::rules::Rules;
+use crate::core::ranking::PontoonRank;
+use crate::core::rules::{Rules, Variant};
+use crate::error::{ConfigError, ConfigResult};
 use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -9,117 +12,1041 @@ pub enum BlackjackPayout {
     Custom { numerator: u32, denominator: u32 },
 }
 
+impl BlackjackPayout {
+    pub fn ratio(self) -> ConfigResult<PayoutRatio> {
+        match self {
+            BlackjackPayout::Standard => Ok(PayoutRatio::THREE_TO_TWO),
+            BlackjackPayout::Vegas => Ok(PayoutRatio::SIX_TO_FIVE),
+            BlackjackPayout::Custom {
+                numerator,
+                denominator,
+            } => PayoutRatio::new(numerator, denominator),
+        }
+    }
+}
+
+/// What happens to the fraction of a credit a ratio like 3:2 leaves on an odd bet.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundingPolicy {
+    #[default]
+    Floor, // the house keeps the fraction
+    RoundHalfUp,
+    HalfChip {
+        chip: u32,
+    }, // paid down to the nearest half of a `chip`-credit chip
+    RejectFractional, // bets that would leave a fraction are refused at betting time
+}
+
+impl RoundingPolicy {
+    pub fn validate(self) -> ConfigResult<()> {
+        match self {
+            RoundingPolicy::HalfChip { chip } if chip == 0 || !chip.is_multiple_of(2) => {
+                Err(ConfigError::InvalidChipSize(chip))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An exact "numerator to denominator" payout. Arithmetic is done in `u64`, where any `u32`
+/// bet times any `u32` numerator fits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutRatio {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl PayoutRatio {
+    pub const EVEN_MONEY: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+    pub const THREE_TO_TWO: Self = Self {
+        numerator: 3,
+        denominator: 2,
+    };
+    pub const SIX_TO_FIVE: Self = Self {
+        numerator: 6,
+        denominator: 5,
+    };
+    pub const TWO_TO_ONE: Self = Self {
+        numerator: 2,
+        denominator: 1,
+    };
+    pub const THREE_TO_ONE: Self = Self {
+        numerator: 3,
+        denominator: 1,
+    };
+    pub const ONE_TO_TWO: Self = Self {
+        numerator: 1,
+        denominator: 2,
+    };
+
+    pub fn new(numerator: u32, denominator: u32) -> ConfigResult<Self> {
+        if denominator == 0 || numerator == 0 {
+            return Err(ConfigError::InvalidPayoutRatio {
+                numerator,
+                denominator,
+            });
+        }
+        Ok(Self {
+            numerator,
+            denominator,
+        })
+    }
+
+    pub fn numerator(self) -> u32 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> u32 {
+        self.denominator
+    }
+
+    /// Smallest bet this ratio pays without a fraction; every exact bet is a multiple of it.
+    pub fn unit(self) -> u32 {
+        self.denominator / gcd(self.numerator, self.denominator)
+    }
+
+    pub fn divides(self, bet: u32) -> bool {
+        bet.is_multiple_of(self.unit())
+    }
+
+    /// Winnings on `bet`, not including the returned stake.
+    pub fn win(self, bet: u32, rounding: RoundingPolicy) -> u64 {
+        let product = bet as u64 * self.numerator as u64;
+        let denominator = self.denominator as u64;
+        let whole = product / denominator;
+        match rounding {
+            RoundingPolicy::Floor | RoundingPolicy::RejectFractional => whole,
+            RoundingPolicy::RoundHalfUp => {
+                if (product % denominator) * 2 >= denominator {
+                    whole + 1
+                } else {
+                    whole
+                }
+            }
+            RoundingPolicy::HalfChip { chip } => {
+                let half = (chip / 2).max(1) as u64;
+                product / (denominator * half) * half
+            }
+        }
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Spanish 21 bonuses on a winning 21. Suited and spaded 6-7-8 / 7-7-7 need all three cards in
+/// one suit (or in spades).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spanish21Paytable {
+    pub five_card_21: PayoutRatio,
+    pub six_card_21: PayoutRatio,
+    pub seven_card_21: PayoutRatio, // seven or more cards
+    pub mixed_678: PayoutRatio,
+    pub suited_678: PayoutRatio,
+    pub spaded_678: PayoutRatio,
+    pub mixed_777: PayoutRatio,
+    pub suited_777: PayoutRatio,
+    pub spaded_777: PayoutRatio,
+}
+
+impl Default for Spanish21Paytable {
+    fn default() -> Self {
+        Self {
+            five_card_21: PayoutRatio::THREE_TO_TWO,
+            six_card_21: PayoutRatio::TWO_TO_ONE,
+            seven_card_21: PayoutRatio::THREE_TO_ONE,
+            mixed_678: PayoutRatio::THREE_TO_TWO,
+            suited_678: PayoutRatio::TWO_TO_ONE,
+            spaded_678: PayoutRatio::THREE_TO_ONE,
+            mixed_777: PayoutRatio::THREE_TO_TWO,
+            suited_777: PayoutRatio::TWO_TO_ONE,
+            spaded_777: PayoutRatio::THREE_TO_ONE,
+        }
+    }
+}
+
+// Side bet paytables quote odds as "X to 1"; a winning side bet returns the stake plus X times it.
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerfectPairsPaytable {
+    pub mixed: u32,
+    pub colored: u32,
+    pub perfect: u32,
+}
+
+impl Default for PerfectPairsPaytable {
+    fn default() -> Self {
+        Self {
+            mixed: 6,
+            colored: 12,
+            perfect: 25,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwentyOnePlus3Paytable {
+    pub flush: u32,
+    pub straight: u32,
+    pub trips: u32,
+    pub straight_flush: u32,
+    pub suited_trips: u32,
+}
+
+impl Default for TwentyOnePlus3Paytable {
+    fn default() -> Self {
+        Self {
+            flush: 5,
+            straight: 10,
+            trips: 30,
+            straight_flush: 40,
+            suited_trips: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoyalMatchPaytable {
+    pub suited: u32,
+    pub royal: u32,
+}
+
+impl Default for RoyalMatchPaytable {
+    fn default() -> Self {
+        Self {
+            suited: 3,
+            royal: 10,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LuckyLadiesPaytable {
+    pub any_20: u32,
+    pub suited_20: u32,
+    pub matched_20: u32,
+    pub queen_of_hearts_pair: u32,
+    pub queen_of_hearts_pair_vs_blackjack: u32,
+}
+
+impl Default for LuckyLadiesPaytable {
+    fn default() -> Self {
+        Self {
+            any_20: 4,
+            suited_20: 10,
+            matched_20: 25,
+            queen_of_hearts_pair: 200,
+            queen_of_hearts_pair_vs_blackjack: 1000,
+        }
+    }
+}
+
+/// Odds by the number of cards in the dealer's busted hand.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BustItPaytable {
+    pub three_cards: u32,
+    pub four_cards: u32,
+    pub five_cards: u32,
+    pub six_cards: u32,
+    pub seven_cards: u32,
+    pub eight_or_more: u32,
+}
+
+impl Default for BustItPaytable {
+    fn default() -> Self {
+        Self {
+            three_cards: 1,
+            four_cards: 2,
+            five_cards: 9,
+            six_cards: 50,
+            seven_cards: 100,
+            eight_or_more: 250,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Super7sPaytable {
+    pub one_seven: u32,
+    pub two_sevens: u32,
+    pub two_suited_sevens: u32,
+    pub three_sevens: u32,
+    pub three_suited_sevens: u32,
+}
+
+impl Default for Super7sPaytable {
+    fn default() -> Self {
+        Self {
+            one_seven: 3,
+            two_sevens: 50,
+            two_suited_sevens: 100,
+            three_sevens: 500,
+            three_suited_sevens: 5000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LuckyLuckyPaytable {
+    pub total_19: u32,
+    pub total_20: u32,
+    pub total_21: u32,
+    pub suited_21: u32,
+    pub six_seven_eight: u32,
+    pub seven_seven_seven: u32,
+    pub suited_six_seven_eight: u32,
+    pub suited_seven_seven_seven: u32,
+}
+
+impl Default for LuckyLuckyPaytable {
+    fn default() -> Self {
+        Self {
+            total_19: 2,
+            total_20: 2,
+            total_21: 3,
+            suited_21: 15,
+            six_seven_eight: 30,
+            seven_seven_seven: 50,
+            suited_six_seven_eight: 100,
+            suited_seven_seven_seven: 200,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PairSquaredPaytable {
+    pub pair: u32,
+    pub suited_pair: u32,
+}
+
+impl Default for PairSquaredPaytable {
+    fn default() -> Self {
+        Self {
+            pair: 10,
+            suited_pair: 25,
+        }
+    }
+}
+
+/// A winning natural spins the wheel; every segment is equally likely.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlackjackSpinPaytable {
+    pub wheel: Vec<u32>,
+}
+
+impl Default for BlackjackSpinPaytable {
+    fn default() -> Self {
+        Self {
+            wheel: vec![3, 3, 3, 3, 5, 5, 5, 10, 10, 25, 50, 100],
+        }
+    }
+}
+
+/// Odds per player card that matches the dealer's upcard rank.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchTheDealerPaytable {
+    pub unsuited_match: u32,
+    pub suited_match: u32,
+}
+
+impl Default for MatchTheDealerPaytable {
+    fn default() -> Self {
+        Self {
+            unsuited_match: 4,
+            suited_match: 11,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusterBlackjackPaytable {
+    pub three_or_four_cards: u32,
+    pub five_cards: u32,
+    pub six_cards: u32,
+    pub seven_cards: u32,
+    pub eight_or_more: u32,
+    pub blackjack_six_cards: u32,
+    pub blackjack_seven_cards: u32,
+    pub blackjack_eight_or_more: u32,
+}
+
+impl Default for BusterBlackjackPaytable {
+    fn default() -> Self {
+        Self {
+            three_or_four_cards: 2,
+            five_cards: 4,
+            six_cards: 18,
+            seven_cards: 50,
+            eight_or_more: 250,
+            blackjack_six_cards: 100,
+            blackjack_seven_cards: 800,
+            blackjack_eight_or_more: 2000,
+        }
+    }
+}
+
+/// Three of a kind across the player's first two cards and the dealer's upcard.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BetTheSetPaytable {
+    pub set: u32,
+    pub suited_set: u32,
+}
+
+impl Default for BetTheSetPaytable {
+    fn default() -> Self {
+        Self {
+            set: 10,
+            suited_set: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Top3Paytable {
+    pub trips: u32,
+    pub straight_flush: u32,
+    pub suited_trips: u32,
+}
+
+impl Default for Top3Paytable {
+    fn default() -> Self {
+        Self {
+            trips: 90,
+            straight_flush: 180,
+            suited_trips: 270,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hot3Paytable {
+    pub total_19: u32,
+    pub total_20: u32,
+    pub total_21: u32,
+    pub suited_21: u32,
+    pub seven_seven_seven: u32,
+}
+
+impl Default for Hot3Paytable {
+    fn default() -> Self {
+        Self {
+            total_19: 1,
+            total_20: 2,
+            total_21: 4,
+            suited_21: 20,
+            seven_seven_seven: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KingsBountyPaytable {
+    pub any_20: u32,
+    pub suited_20: u32,
+    pub suited_pair: u32,
+    pub suited_kings: u32,
+    pub kings_of_spades: u32,
+    pub kings_of_spades_vs_blackjack: u32,
+}
+
+impl Default for KingsBountyPaytable {
+    fn default() -> Self {
+        Self {
+            any_20: 4,
+            suited_20: 9,
+            suited_pair: 30,
+            suited_kings: 100,
+            kings_of_spades: 200,
+            kings_of_spades_vs_blackjack: 1000,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PairKind {
+    Mixed,
+    Colored,
+    Perfect,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThreeCardHand {
+    Flush,
+    Straight,
+    Trips,
+    StraightFlush,
+    SuitedTrips,
+}
+
 pub fn calculate_payout(bet: u32, player_hand: &Hand, dealer_hand: &Hand, rules: &Rules) -> u64 {
     if player_hand.is_bust() {
         return 0;
     }
 
+    let spanish = rules.variant == Variant::Spanish21;
+    let dealer_wins_ties = rules.variant == Variant::DoubleExposure;
+    match (player_hand.is_blackjack(), dealer_hand.is_blackjack()) {
+        (true, true) if dealer_wins_ties => return 0,
+        (true, true) if !spanish => return bet as u64,
+        (true, _) => {
+            return bet as u64 + calculate_payout_for_blackjack(bet, rules);
+        }
+        (false, true) => return 0,
+        (false, false) => {}
+    }
+
+    let dealer_hand_value = dealer_hand.value();
+    if rules.dealer_22_pushes() && dealer_hand_value == 22 {
+        return bet as u64;
+    }
+
     if dealer_hand.is_bust() {
         return bet as u64 * 2;
     }
 
     let player_hand_value = player_hand.value();
-    let dealer_hand_value = dealer_hand.value();
+    if spanish && player_hand_value == 21 {
+        return bet as u64 * 2;
+    }
 
     match player_hand_value.cmp(&dealer_hand_value) {
-        std::cmp::Ordering::Greater => {
-            if player_hand.is_blackjack() {
-                bet as u64 + calculate_payout_for_blackjack(bet, rules.blackjack_payout)
-            } else {
-                bet as u64 * 2
-            }
-        }
+        std::cmp::Ordering::Greater => bet as u64 * 2,
         std::cmp::Ordering::Less => 0,
+        std::cmp::Ordering::Equal if dealer_wins_ties => 0,
         std::cmp::Ordering::Equal => bet as u64,
     }
 }
 
-fn calculate_payout_for_blackjack(bet: u32, blackjack_payout: BlackjackPayout) -> u64 {
-    match blackjack_payout {
-        BlackjackPayout::Standard => (bet * 3 / 2) as u64,
-        BlackjackPayout::Vegas => (bet * 6 / 5) as u64,
-        BlackjackPayout::Custom {
-            numerator,
-            denominator,
-        } => (bet * numerator / denominator) as u64,
+fn calculate_payout_for_blackjack(bet: u32, rules: &Rules) -> u64 {
+    // An invalid custom ratio is refused when the table is created; should one slip through,
+    // the natural is paid as an ordinary win.
+    let ratio = rules.natural_payout().unwrap_or(PayoutRatio::EVEN_MONEY);
+    ratio.win(bet, rules.payout_rounding)
+}
+
+/// The Spanish 21 bonus a 21 earns, if any.
+pub fn spanish_21_bonus(player_hand: &Hand, paytable: &Spanish21Paytable) -> Option<PayoutRatio> {
+    let cards = player_hand.cards();
+    if player_hand.value() != 21 || player_hand.is_blackjack() {
+        return None;
+    }
+
+    if let [a, b, c] = cards {
+        let mut ranks = [a.rank, b.rank, c.rank];
+        ranks.sort_by_key(|r| *r as u8);
+        let combo = match ranks {
+            [Rank::Six, Rank::Seven, Rank::Eight] => {
+                Some((paytable.mixed_678, paytable.suited_678, paytable.spaded_678))
+            }
+            [Rank::Seven, Rank::Seven, Rank::Seven] => {
+                Some((paytable.mixed_777, paytable.suited_777, paytable.spaded_777))
+            }
+            _ => None,
+        };
+        if let Some((mixed, suited, spaded)) = combo {
+            let same_suit = a.suit == b.suit && b.suit == c.suit;
+            return Some(match (same_suit, a.suit) {
+                (true, Suit::Spades) => spaded,
+                (true, _) => suited,
+                (false, _) => mixed,
+            });
+        }
+    }
+
+    match cards.len() {
+        0..=4 => None,
+        5 => Some(paytable.five_card_21),
+        6 => Some(paytable.six_card_21),
+        _ => Some(paytable.seven_card_21),
+    }
+}
+
+/// Spanish 21 settlement for one hand: a bonus 21 is paid at its bonus ratio unless the hand
+/// was doubled, everything else goes through [`calculate_payout`].
+pub fn calculate_spanish_21_payout(
+    bet: u32,
+    player_hand: &Hand,
+    dealer_hand: &Hand,
+    doubled: bool,
+    rules: &Rules,
+) -> u64 {
+    let bonus = (!doubled)
+        .then(|| spanish_21_bonus(player_hand, &rules.spanish_21_paytable))
+        .flatten();
+    match bonus {
+        // Player 21 always wins, so a bonus hand is paid whatever the dealer made.
+        Some(ratio) if !dealer_hand.is_blackjack() => {
+            bet as u64 + ratio.win(bet, rules.payout_rounding)
+        }
+        _ => calculate_payout(bet, player_hand, dealer_hand, rules),
+    }
+}
+
+/// Pontoon settlement: hands compare by [`PontoonRank`] and the banker takes every tie. A
+/// pontoon pays the natural ratio and a five-card trick pays 2:1.
+pub fn calculate_pontoon_payout(
+    bet: u32,
+    player_hand: &Hand,
+    dealer_hand: &Hand,
+    rules: &Rules,
+) -> u64 {
+    let player = PontoonRank::of(player_hand);
+    if player == PontoonRank::Bust || player <= PontoonRank::of(dealer_hand) {
+        return 0;
+    }
+
+    let ratio = match player {
+        PontoonRank::Pontoon => rules.natural_payout().unwrap_or(PayoutRatio::EVEN_MONEY),
+        PontoonRank::FiveCardTrick => PayoutRatio::TWO_TO_ONE,
+        _ => PayoutRatio::EVEN_MONEY,
+    };
+    bet as u64 + ratio.win(bet, rules.payout_rounding)
+}
+
+/// Half the bet back, rounded by the table's policy.
+pub fn calculate_surrender_payout(bet: u32, dealer_hand: &Hand, rules: &Rules) -> u64 {
+    // A surrender made after the early window, without a hole card checked, can still run into
+    // a dealer natural.
+    let late = dealer_hand
+        .cards()
+        .first()
+        .is_none_or(|&upcard| !rules.surrender.allows_early(upcard));
+    if late && !rules.dealer_has_hole_card() && dealer_hand.is_blackjack() {
+        return 0;
     }
+
+    PayoutRatio::ONE_TO_TWO.win(bet, rules.payout_rounding)
 }
 
 pub fn calculate_insurance_payout(insurance_bet: u32, dealer_hand: &Hand) -> u64 {
     if dealer_hand.is_blackjack() {
-        insurance_bet as u64 * 2
+        insurance_bet as u64 * 3 // 2:1 plus the stake
     } else {
         0
     }
 }
 
-pub fn calculate_perfect_pairs_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Perfect Pairs - v0.2")
+pub fn evaluate_perfect_pairs(player_hand: &Hand) -> Option<PairKind> {
+    let [a, b] = first_two(player_hand)?;
+    if a.rank != b.rank {
+        return None;
+    }
+
+    Some(if a.suit == b.suit {
+        PairKind::Perfect
+    } else if a.suit.is_red() == b.suit.is_red() {
+        PairKind::Colored
+    } else {
+        PairKind::Mixed
+    })
 }
 
-pub fn calculate_twenty_plus_3_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Twenty Plus 3 - v0.2")
+pub fn calculate_perfect_pairs_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    paytable: &PerfectPairsPaytable,
+) -> u64 {
+    let odds = match evaluate_perfect_pairs(player_hand) {
+        Some(PairKind::Mixed) => paytable.mixed,
+        Some(PairKind::Colored) => paytable.colored,
+        Some(PairKind::Perfect) => paytable.perfect,
+        None => return 0,
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_royal_match_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Royal Match - v0.2")
+/// Poker hand made by the player's first two cards and the dealer's upcard.
+pub fn evaluate_twenty_one_plus_3(
+    player_hand: &Hand,
+    dealer_upcard: Card,
+) -> Option<ThreeCardHand> {
+    let [a, b] = first_two(player_hand)?;
+    let cards = [a, b, dealer_upcard];
+
+    let suited = cards.iter().all(|c| c.suit == a.suit);
+    let trips = cards.iter().all(|c| c.rank == a.rank);
+    let straight = is_straight(cards.map(|c| c.rank));
+
+    match (suited, trips, straight) {
+        (true, true, _) => Some(ThreeCardHand::SuitedTrips),
+        (true, _, true) => Some(ThreeCardHand::StraightFlush),
+        (false, true, _) => Some(ThreeCardHand::Trips),
+        (false, _, true) => Some(ThreeCardHand::Straight),
+        (true, false, false) => Some(ThreeCardHand::Flush),
+        (false, false, false) => None,
+    }
 }
 
-pub fn calculate_lucky_ladies_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Lucky Ladies - v0.2")
+pub fn calculate_twenty_plus_3_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &TwentyOnePlus3Paytable,
+) -> u64 {
+    let odds = match evaluate_twenty_one_plus_3(player_hand, dealer_upcard) {
+        Some(ThreeCardHand::Flush) => paytable.flush,
+        Some(ThreeCardHand::Straight) => paytable.straight,
+        Some(ThreeCardHand::Trips) => paytable.trips,
+        Some(ThreeCardHand::StraightFlush) => paytable.straight_flush,
+        Some(ThreeCardHand::SuitedTrips) => paytable.suited_trips,
+        None => return 0,
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_bust_it_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Bust It - v0.2")
+pub fn calculate_royal_match_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    paytable: &RoyalMatchPaytable,
+) -> u64 {
+    let Some([a, b]) = first_two(player_hand) else {
+        return 0;
+    };
+    if a.suit != b.suit {
+        return 0;
+    }
+
+    let royal = matches!(
+        (a.rank, b.rank),
+        (Rank::King, Rank::Queen) | (Rank::Queen, Rank::King)
+    );
+    side_bet_return(
+        side_bet,
+        if royal {
+            paytable.royal
+        } else {
+            paytable.suited
+        },
+    )
 }
 
-pub fn calculate_super_7s_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Super 7s - v0.2")
+fn two_card_total(a: Card, b: Card) -> u8 {
+    let mut hand = Hand::new();
+    hand.add_card(a);
+    hand.add_card(b);
+    hand.value()
 }
 
-pub fn calculate_lucky_lucky_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Lucky Lucky - v0.2")
+fn first_two(hand: &Hand) -> Option<[Card; 2]> {
+    match hand.cards() {
+        [a, b, ..] => Some([*a, *b]),
+        _ => None,
+    }
 }
 
-pub fn calculate_pair_squared_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Pair Squared - v0.2")
+// Three distinct consecutive ranks; the Ace plays high (Q-K-A) or low (A-2-3).
+fn is_straight(ranks: [Rank; 3]) -> bool {
+    let mut order = ranks.map(|r| r as u8);
+    order.sort_unstable();
+    let consecutive = |o: [u8; 3]| o[1] == o[0] + 1 && o[2] == o[1] + 1;
+    if consecutive(order) {
+        return true;
+    }
+
+    let ace = Rank::Ace as u8;
+    if order[2] == ace {
+        // Ace low: A-2-3 sorts as 2, 3, A
+        return order[0] == Rank::Two as u8 && order[1] == Rank::Three as u8;
+    }
+    false
 }
 
-pub fn calculate_blackjack_spin_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Blackjack Spin - v0.2")
+fn side_bet_return(side_bet: u32, odds: u32) -> u64 {
+    side_bet as u64 * (odds as u64 + 1)
 }
 
-pub fn calculate_match_the_deal_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Match the Deal - v0.2")
+pub fn calculate_lucky_ladies_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_hand: &Hand,
+    paytable: &LuckyLadiesPaytable,
+) -> u64 {
+    let Some([a, b]) = first_two(player_hand) else {
+        return 0;
+    };
+    if two_card_total(a, b) != 20 {
+        return 0;
+    }
+
+    let queen_of_hearts = Card::new(Rank::Queen, Suit::Hearts);
+    let odds = if a == queen_of_hearts && b == queen_of_hearts {
+        if dealer_hand.is_blackjack() {
+            paytable.queen_of_hearts_pair_vs_blackjack
+        } else {
+            paytable.queen_of_hearts_pair
+        }
+    } else if a == b {
+        paytable.matched_20
+    } else if a.suit == b.suit {
+        paytable.suited_20
+    } else {
+        paytable.any_20
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_buster_blackjack_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Buster Blackjack - v0.2")
+pub fn calculate_bust_it_payout(
+    side_bet: u32,
+    dealer_hand: &Hand,
+    paytable: &BustItPaytable,
+) -> u64 {
+    if !dealer_hand.is_bust() {
+        return 0;
+    }
+
+    let odds = match dealer_hand.cards().len() {
+        0..=3 => paytable.three_cards,
+        4 => paytable.four_cards,
+        5 => paytable.five_cards,
+        6 => paytable.six_cards,
+        7 => paytable.seven_cards,
+        _ => paytable.eight_or_more,
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_bet_the_set_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Bet the Set - v0.2")
+/// Pays on sevens among the first three cards the player receives, stopping at the first
+/// card that is not a seven.
+pub fn calculate_super_7s_payout(
+    side_bet: u32,
+    player_cards: &[Card],
+    paytable: &Super7sPaytable,
+) -> u64 {
+    let sevens: Vec<Card> = player_cards
+        .iter()
+        .take(3)
+        .take_while(|c| c.rank == Rank::Seven)
+        .copied()
+        .collect();
+    let suited = sevens.iter().all(|c| c.suit == sevens[0].suit);
+
+    let odds = match (sevens.len(), suited) {
+        (0, _) => return 0,
+        (1, _) => paytable.one_seven,
+        (2, false) => paytable.two_sevens,
+        (2, true) => paytable.two_suited_sevens,
+        (_, false) => paytable.three_sevens,
+        (_, true) => paytable.three_suited_sevens,
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_top_3_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Top 3 - v0.2")
+/// The player's first two cards and the dealer's upcard, counted as one three-card total.
+pub fn calculate_lucky_lucky_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &LuckyLuckyPaytable,
+) -> u64 {
+    let Some([a, b]) = first_two(player_hand) else {
+        return 0;
+    };
+
+    let mut three = Hand::new();
+    for card in [a, b, dealer_upcard] {
+        three.add_card(card);
+    }
+
+    let suited = a.suit == b.suit && b.suit == dealer_upcard.suit;
+    let mut ranks = [a.rank, b.rank, dealer_upcard.rank];
+    ranks.sort_unstable_by_key(|r| *r as u8);
+
+    let odds = match (ranks, suited, three.value()) {
+        ([Rank::Seven, Rank::Seven, Rank::Seven], true, _) => paytable.suited_seven_seven_seven,
+        ([Rank::Six, Rank::Seven, Rank::Eight], true, _) => paytable.suited_six_seven_eight,
+        ([Rank::Seven, Rank::Seven, Rank::Seven], false, _) => paytable.seven_seven_seven,
+        ([Rank::Six, Rank::Seven, Rank::Eight], false, _) => paytable.six_seven_eight,
+        (_, true, 21) => paytable.suited_21,
+        (_, false, 21) => paytable.total_21,
+        (_, _, 20) => paytable.total_20,
+        (_, _, 19) => paytable.total_19,
+        _ => return 0,
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_hot_3_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Hot 3 - v0.2")
+pub fn calculate_pair_squared_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    paytable: &PairSquaredPaytable,
+) -> u64 {
+    let odds = match evaluate_perfect_pairs(player_hand) {
+        Some(PairKind::Perfect) => paytable.suited_pair,
+        Some(_) => paytable.pair,
+        None => return 0,
+    };
+    side_bet_return(side_bet, odds)
 }
 
-pub fn calculate_kings_bounty_payout(_side_bet: u32, _player_hand: &Hand) -> u64 {
-    unimplemented!("Kings Bounty - v0.2")
+/// `spin` is the random draw for the wheel; it only matters when the player has a natural.
+pub fn calculate_blackjack_spin_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    spin: u32,
+    paytable: &BlackjackSpinPaytable,
+) -> u64 {
+    if !player_hand.is_blackjack() || paytable.wheel.is_empty() {
+        return 0;
+    }
+
+    let segment = spin as usize % paytable.wheel.len();
+    side_bet_return(side_bet, paytable.wheel[segment])
 }
 
-// missing progressives: caribbean_21, blazing_7s
+/// Each of the player's first two cards is matched against the dealer's upcard separately and
+/// the wins add up.
+pub fn calculate_match_the_dealer_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &MatchTheDealerPaytable,
+) -> u64 {
+    let Some(cards) = first_two(player_hand) else {
+        return 0;
+    };
+
+    let odds: u32 = cards
+        .iter()
+        .filter(|c| c.rank == dealer_upcard.rank)
+        .map(|c| {
+            if c.suit == dealer_upcard.suit {
+                paytable.suited_match
+            } else {
+                paytable.unsuited_match
+            }
+        })
+        .sum();
+
+    if odds == 0 {
+        return 0;
+    }
+    side_bet_return(side_bet, odds)
+}
+
+pub fn calculate_buster_blackjack_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_hand: &Hand,
+    paytable: &BusterBlackjackPaytable,
+) -> u64 {
+    if !dealer_hand.is_bust() {
+        return 0;
+    }
+
+    let odds = match (dealer_hand.cards().len(), player_hand.is_blackjack()) {
+        (0..=4, _) => paytable.three_or_four_cards,
+        (5, _) => paytable.five_cards,
+        (6, false) => paytable.six_cards,
+        (6, true) => paytable.blackjack_six_cards,
+        (7, false) => paytable.seven_cards,
+        (7, true) => paytable.blackjack_seven_cards,
+        (_, false) => paytable.eight_or_more,
+        (_, true) => paytable.blackjack_eight_or_more,
+    };
+    side_bet_return(side_bet, odds)
+}
+
+pub fn calculate_bet_the_set_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &BetTheSetPaytable,
+) -> u64 {
+    let odds = match evaluate_twenty_one_plus_3(player_hand, dealer_upcard) {
+        Some(ThreeCardHand::SuitedTrips) => paytable.suited_set,
+        Some(ThreeCardHand::Trips) => paytable.set,
+        _ => return 0,
+    };
+    side_bet_return(side_bet, odds)
+}
+
+pub fn calculate_top_3_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &Top3Paytable,
+) -> u64 {
+    let odds = match evaluate_twenty_one_plus_3(player_hand, dealer_upcard) {
+        Some(ThreeCardHand::SuitedTrips) => paytable.suited_trips,
+        Some(ThreeCardHand::StraightFlush) => paytable.straight_flush,
+        Some(ThreeCardHand::Trips) => paytable.trips,
+        _ => return 0,
+    };
+    side_bet_return(side_bet, odds)
+}
+
+pub fn calculate_hot_3_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &Hot3Paytable,
+) -> u64 {
+    let Some([a, b]) = first_two(player_hand) else {
+        return 0;
+    };
+
+    let cards = [a, b, dealer_upcard];
+    let mut three = Hand::new();
+    for card in cards {
+        three.add_card(card);
+    }
+    let suited = cards.iter().all(|c| c.suit == a.suit);
+
+    let odds = if cards.iter().all(|c| c.rank == Rank::Seven) {
+        paytable.seven_seven_seven
+    } else {
+        match (three.value(), suited) {
+            (21, true) => paytable.suited_21,
+            (21, false) => paytable.total_21,
+            (20, _) => paytable.total_20,
+            (19, _) => paytable.total_19,
+            _ => return 0,
+        }
+    };
+    side_bet_return(side_bet, odds)
+}
+
+pub fn calculate_kings_bounty_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_hand: &Hand,
+    paytable: &KingsBountyPaytable,
+) -> u64 {
+    let Some([a, b]) = first_two(player_hand) else {
+        return 0;
+    };
+    if two_card_total(a, b) != 20 {
+        return 0;
+    }
+
+    let king_of_spades = Card::new(Rank::King, Suit::Spades);
+    let odds = if a == king_of_spades && b == king_of_spades {
+        if dealer_hand.is_blackjack() {
+            paytable.kings_of_spades_vs_blackjack
+        } else {
+            paytable.kings_of_spades
+        }
+    } else if a == b && a.rank == Rank::King {
+        paytable.suited_kings
+    } else if a == b {
+        paytable.suited_pair
+    } else if a.suit == b.suit {
+        paytable.suited_20
+    } else {
+        paytable.any_20
+    };
+    side_bet_return(side_bet, odds)
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::card::{Card, Rank, Suit};
     use crate::core::hand::Hand;
+    use crate::core::rules::{HoleCardRule, SurrenderRule};
 
     fn make_rules() -> Rules {
         Rules {
@@ -130,7 +1057,8 @@ mod tests {
             num_decks: 6,
             split_limit: 3,
             dealer_hits_soft_17: false,
-            surrender_allowed: false,
+            hole_card: HoleCardRule::Peek,
+            surrender: SurrenderRule::None,
             resplit_aces_allowed: false,
             hit_split_aces_allowed: false,
             double_after_split_allowed: true,
@@ -138,6 +1066,10 @@ mod tests {
             perfect_pairs_enabled: false,
             twenty_one_plus_3_enabled: false,
             royal_match_enabled: false,
+            perfect_pairs_paytable: Some(PerfectPairsPaytable::default()),
+            twenty_one_plus_3_paytable: Some(TwentyOnePlus3Paytable::default()),
+            royal_match_paytable: Some(RoyalMatchPaytable::default()),
+            ..Rules::new()
         }
     }
 
@@ -220,6 +1152,78 @@ mod tests {
         assert_eq!(calculate_payout(100, &player, &dealer, &rules), 100);
     }
 
+    #[test]
+    fn dealer_blackjack_beats_21() {
+        let rules = make_rules();
+        let player = make_hand(&[
+            (Rank::Seven, Suit::Spades),
+            (Rank::Seven, Suit::Hearts),
+            (Rank::Seven, Suit::Clubs),
+        ]);
+        let dealer = make_hand(&[(Rank::Ace, Suit::Hearts), (Rank::Queen, Suit::Spades)]);
+
+        assert_eq!(calculate_payout(100, &player, &dealer, &rules), 0);
+    }
+
+    #[test]
+    fn split_21_pays_even_money() {
+        let rules = make_rules();
+        let mut player = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::Ace, Suit::Hearts)]);
+        player.split_off().unwrap();
+        player.add_card(Card::new(Rank::King, Suit::Hearts));
+        let dealer = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+
+        assert_eq!(calculate_payout(100, &player, &dealer, &rules), 200);
+    }
+
+    #[test]
+    fn surrender_returns_half() {
+        let mut rules = make_rules();
+        rules.surrender = SurrenderRule::Late;
+        let dealer = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+
+        assert_eq!(calculate_surrender_payout(100, &dealer, &rules), 50);
+    }
+
+    #[test]
+    fn late_surrender_without_hole_card_loses_to_natural() {
+        let mut rules = make_rules();
+        rules.surrender = SurrenderRule::Late;
+        rules.hole_card = HoleCardRule::Enhc;
+        let dealer = make_hand(&[(Rank::Ace, Suit::Hearts), (Rank::Queen, Suit::Spades)]);
+
+        assert_eq!(calculate_surrender_payout(100, &dealer, &rules), 0);
+
+        rules.surrender = SurrenderRule::Early;
+        assert_eq!(calculate_surrender_payout(100, &dealer, &rules), 50);
+
+        // early against a ten but late against an Ace
+        rules.surrender = SurrenderRule::EarlyExceptAce;
+        assert_eq!(calculate_surrender_payout(100, &dealer, &rules), 0);
+        let ten_up = make_hand(&[(Rank::King, Suit::Spades), (Rank::Ace, Suit::Hearts)]);
+        assert_eq!(calculate_surrender_payout(100, &ten_up, &rules), 50);
+    }
+
+    #[test]
+    fn surrender_rounds_odd_bets_by_policy() {
+        let mut rules = make_rules();
+        rules.surrender = SurrenderRule::Late;
+        let dealer = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+
+        assert_eq!(calculate_surrender_payout(15, &dealer, &rules), 7);
+        rules.payout_rounding = RoundingPolicy::RoundHalfUp;
+        assert_eq!(calculate_surrender_payout(15, &dealer, &rules), 8);
+    }
+
+    #[test]
+    fn insurance_pays_2_to_1() {
+        let dealer = make_hand(&[(Rank::Ace, Suit::Hearts), (Rank::Queen, Suit::Spades)]);
+        assert_eq!(calculate_insurance_payout(50, &dealer), 150);
+
+        let dealer = make_hand(&[(Rank::Ace, Suit::Hearts), (Rank::Six, Suit::Spades)]);
+        assert_eq!(calculate_insurance_payout(50, &dealer), 0);
+    }
+
     #[test]
     fn blackjack_vegas_payout() {
         let mut rules = make_rules();
@@ -244,4 +1248,658 @@ mod tests {
 
         assert_eq!(calculate_payout(100, &player, &dealer, &rules), 300);
     }
+
+    #[test]
+    fn odd_bet_natural_rounding() {
+        let mut rules = make_rules();
+        let player = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let dealer = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+
+        // 3:2 on 5 is 7.5
+        assert_eq!(calculate_payout(5, &player, &dealer, &rules), 12);
+        rules.payout_rounding = RoundingPolicy::RoundHalfUp;
+        assert_eq!(calculate_payout(5, &player, &dealer, &rules), 13);
+    }
+
+    #[test]
+    fn half_chip_rounding() {
+        let six_five = PayoutRatio::SIX_TO_FIVE;
+        // 6:5 on 9 is 10.8: a 4-credit chip pays down to 2-credit halves
+        assert_eq!(six_five.win(9, RoundingPolicy::HalfChip { chip: 4 }), 10);
+        assert_eq!(six_five.win(9, RoundingPolicy::Floor), 10);
+        // 3:2 on 5 is 7.5: 10-credit chips pay in fives
+        let three_two = PayoutRatio::THREE_TO_TWO;
+        assert_eq!(three_two.win(5, RoundingPolicy::HalfChip { chip: 10 }), 5);
+        assert!(RoundingPolicy::HalfChip { chip: 3 }.validate().is_err());
+    }
+
+    #[test]
+    fn ratio_units() {
+        assert_eq!(PayoutRatio::THREE_TO_TWO.unit(), 2);
+        assert_eq!(PayoutRatio::SIX_TO_FIVE.unit(), 5);
+        assert_eq!(PayoutRatio::new(4, 2).unwrap().unit(), 1);
+        assert!(PayoutRatio::SIX_TO_FIVE.divides(25));
+        assert!(!PayoutRatio::THREE_TO_TWO.divides(5));
+    }
+
+    #[test]
+    fn large_bets_do_not_overflow() {
+        let win = PayoutRatio::THREE_TO_TWO.win(u32::MAX, RoundingPolicy::Floor);
+        assert_eq!(win, u32::MAX as u64 * 3 / 2);
+    }
+
+    #[test]
+    fn zero_denominator_rejected() {
+        let payout = BlackjackPayout::Custom {
+            numerator: 3,
+            denominator: 0,
+        };
+        assert!(matches!(
+            payout.ratio(),
+            Err(ConfigError::InvalidPayoutRatio { .. })
+        ));
+
+        // never panics, even if it reaches settlement
+        let rules = Rules {
+            blackjack_payout: payout,
+            ..make_rules()
+        };
+        let player = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let dealer = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+        assert_eq!(calculate_payout(10, &player, &dealer, &rules), 20);
+    }
+
+    #[test]
+    fn switch_naturals_and_dealer_22() {
+        let rules = Rules::blackjack_switch();
+        let natural = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let twenty = make_hand(&[(Rank::King, Suit::Spades), (Rank::Queen, Suit::Hearts)]);
+        let dealer_22 = make_hand(&[
+            (Rank::Ten, Suit::Clubs),
+            (Rank::Six, Suit::Clubs),
+            (Rank::Six, Suit::Hearts),
+        ]);
+        let dealer_23 = make_hand(&[
+            (Rank::Ten, Suit::Clubs),
+            (Rank::Six, Suit::Clubs),
+            (Rank::Seven, Suit::Hearts),
+        ]);
+        let dealer_19 = make_hand(&[(Rank::Ten, Suit::Clubs), (Rank::Nine, Suit::Clubs)]);
+
+        assert_eq!(calculate_payout(10, &natural, &dealer_19, &rules), 20);
+        assert_eq!(calculate_payout(10, &natural, &dealer_22, &rules), 20);
+        assert_eq!(calculate_payout(10, &twenty, &dealer_22, &rules), 10);
+        assert_eq!(calculate_payout(10, &twenty, &dealer_23, &rules), 20);
+    }
+
+    #[test]
+    fn double_exposure_dealer_wins_ties() {
+        let rules = Rules::double_exposure();
+        let natural = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let nineteen = make_hand(&[(Rank::King, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+        let dealer_19 = make_hand(&[(Rank::Ten, Suit::Clubs), (Rank::Nine, Suit::Clubs)]);
+        let dealer_natural = make_hand(&[(Rank::Ace, Suit::Clubs), (Rank::Queen, Suit::Clubs)]);
+
+        assert_eq!(calculate_payout(10, &nineteen, &dealer_19, &rules), 0);
+        assert_eq!(calculate_payout(10, &natural, &dealer_natural, &rules), 0);
+        assert_eq!(calculate_payout(10, &natural, &dealer_19, &rules), 20);
+    }
+
+    #[test]
+    fn free_bet_dealer_22_pushes() {
+        let rules = Rules::free_bet();
+        let natural = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let twenty = make_hand(&[(Rank::King, Suit::Spades), (Rank::Queen, Suit::Hearts)]);
+        let dealer_22 = make_hand(&[
+            (Rank::Ten, Suit::Clubs),
+            (Rank::Six, Suit::Clubs),
+            (Rank::Six, Suit::Hearts),
+        ]);
+
+        assert_eq!(calculate_payout(10, &twenty, &dealer_22, &rules), 10);
+        assert_eq!(calculate_payout(10, &natural, &dealer_22, &rules), 25);
+    }
+
+    #[test]
+    fn pontoon_rankings_pay() {
+        let rules = Rules::pontoon();
+        let pontoon = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let trick = make_hand(&[
+            (Rank::Two, Suit::Spades),
+            (Rank::Three, Suit::Hearts),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Two, Suit::Clubs),
+            (Rank::Five, Suit::Clubs),
+        ]);
+        let twenty = make_hand(&[(Rank::King, Suit::Spades), (Rank::Queen, Suit::Hearts)]);
+        let dealer_20 = make_hand(&[(Rank::Ten, Suit::Clubs), (Rank::Jack, Suit::Clubs)]);
+        let dealer_21 = make_hand(&[
+            (Rank::Ten, Suit::Clubs),
+            (Rank::Six, Suit::Clubs),
+            (Rank::Five, Suit::Hearts),
+        ]);
+        let dealer_pontoon = make_hand(&[(Rank::Ace, Suit::Clubs), (Rank::Queen, Suit::Clubs)]);
+
+        assert_eq!(calculate_pontoon_payout(10, &twenty, &dealer_20, &rules), 0);
+        assert_eq!(calculate_pontoon_payout(10, &trick, &dealer_21, &rules), 30);
+        assert_eq!(
+            calculate_pontoon_payout(10, &pontoon, &dealer_21, &rules),
+            30
+        );
+        assert_eq!(
+            calculate_pontoon_payout(10, &pontoon, &dealer_pontoon, &rules),
+            0
+        );
+        assert_eq!(
+            calculate_pontoon_payout(10, &trick, &dealer_pontoon, &rules),
+            0
+        );
+
+        // a table that configures its own pontoon ratio gets it
+        let three_to_two = Rules {
+            blackjack_payout: BlackjackPayout::Standard,
+            ..Rules::pontoon()
+        };
+        assert_eq!(
+            calculate_pontoon_payout(10, &pontoon, &dealer_21, &three_to_two),
+            25
+        );
+        assert_eq!(
+            calculate_pontoon_payout(10, &trick, &dealer_21, &three_to_two),
+            30
+        );
+    }
+
+    #[test]
+    fn spanish_21_bonuses() {
+        let paytable = Spanish21Paytable::default();
+        let mixed = make_hand(&[
+            (Rank::Six, Suit::Hearts),
+            (Rank::Eight, Suit::Spades),
+            (Rank::Seven, Suit::Clubs),
+        ]);
+        let spaded = make_hand(&[
+            (Rank::Seven, Suit::Spades),
+            (Rank::Seven, Suit::Spades),
+            (Rank::Seven, Suit::Spades),
+        ]);
+        let five_card = make_hand(&[
+            (Rank::Two, Suit::Hearts),
+            (Rank::Three, Suit::Spades),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Five, Suit::Clubs),
+            (Rank::Seven, Suit::Clubs),
+        ]);
+        let three_card = make_hand(&[
+            (Rank::Nine, Suit::Hearts),
+            (Rank::Five, Suit::Spades),
+            (Rank::Seven, Suit::Clubs),
+        ]);
+
+        assert_eq!(
+            spanish_21_bonus(&mixed, &paytable),
+            Some(PayoutRatio::THREE_TO_TWO)
+        );
+        assert_eq!(
+            spanish_21_bonus(&spaded, &paytable),
+            Some(PayoutRatio::THREE_TO_ONE)
+        );
+        assert_eq!(
+            spanish_21_bonus(&five_card, &paytable),
+            Some(PayoutRatio::THREE_TO_TWO)
+        );
+        assert_eq!(spanish_21_bonus(&three_card, &paytable), None);
+    }
+
+    #[test]
+    fn spanish_21_player_21_always_wins() {
+        let rules = Rules::spanish_21();
+        let player = make_hand(&[
+            (Rank::Nine, Suit::Hearts),
+            (Rank::Five, Suit::Spades),
+            (Rank::Seven, Suit::Clubs),
+        ]);
+        let dealer = make_hand(&[
+            (Rank::King, Suit::Hearts),
+            (Rank::Five, Suit::Spades),
+            (Rank::Six, Suit::Clubs),
+        ]);
+        assert_eq!(calculate_payout(10, &player, &dealer, &rules), 20);
+        assert_eq!(calculate_payout(10, &player, &dealer, &make_rules()), 10);
+
+        let natural = make_hand(&[(Rank::Ace, Suit::Hearts), (Rank::King, Suit::Spades)]);
+        let dealer_natural = make_hand(&[(Rank::Ace, Suit::Clubs), (Rank::Jack, Suit::Spades)]);
+        assert_eq!(calculate_payout(10, &natural, &dealer_natural, &rules), 25);
+    }
+
+    #[test]
+    fn spanish_21_bonus_void_when_doubled() {
+        let rules = Rules::spanish_21();
+        let player = make_hand(&[
+            (Rank::Six, Suit::Hearts),
+            (Rank::Seven, Suit::Hearts),
+            (Rank::Eight, Suit::Hearts),
+        ]);
+        let dealer = make_hand(&[(Rank::King, Suit::Hearts), (Rank::Nine, Suit::Spades)]);
+
+        assert_eq!(
+            calculate_spanish_21_payout(10, &player, &dealer, false, &rules),
+            30
+        );
+        assert_eq!(
+            calculate_spanish_21_payout(20, &player, &dealer, true, &rules),
+            40
+        );
+    }
+
+    #[test]
+    fn perfect_pairs_kinds() {
+        let paytable = PerfectPairsPaytable::default();
+
+        let perfect = make_hand(&[(Rank::Eight, Suit::Spades), (Rank::Eight, Suit::Spades)]);
+        let colored = make_hand(&[(Rank::Eight, Suit::Spades), (Rank::Eight, Suit::Clubs)]);
+        let mixed = make_hand(&[(Rank::Eight, Suit::Spades), (Rank::Eight, Suit::Hearts)]);
+        let none = make_hand(&[(Rank::King, Suit::Spades), (Rank::Queen, Suit::Spades)]);
+
+        assert_eq!(evaluate_perfect_pairs(&perfect), Some(PairKind::Perfect));
+        assert_eq!(evaluate_perfect_pairs(&colored), Some(PairKind::Colored));
+        assert_eq!(evaluate_perfect_pairs(&mixed), Some(PairKind::Mixed));
+        assert_eq!(evaluate_perfect_pairs(&none), None);
+
+        assert_eq!(calculate_perfect_pairs_payout(10, &perfect, &paytable), 260);
+        assert_eq!(calculate_perfect_pairs_payout(10, &colored, &paytable), 130);
+        assert_eq!(calculate_perfect_pairs_payout(10, &mixed, &paytable), 70);
+        assert_eq!(calculate_perfect_pairs_payout(10, &none, &paytable), 0);
+    }
+
+    #[test]
+    fn twenty_one_plus_3_hands() {
+        let upcard = Card::new(Rank::Nine, Suit::Hearts);
+        let cases = [
+            (
+                [(Rank::Nine, Suit::Hearts), (Rank::Nine, Suit::Hearts)],
+                ThreeCardHand::SuitedTrips,
+            ),
+            (
+                [(Rank::Seven, Suit::Hearts), (Rank::Eight, Suit::Hearts)],
+                ThreeCardHand::StraightFlush,
+            ),
+            (
+                [(Rank::Nine, Suit::Clubs), (Rank::Nine, Suit::Spades)],
+                ThreeCardHand::Trips,
+            ),
+            (
+                [(Rank::Ten, Suit::Clubs), (Rank::Jack, Suit::Spades)],
+                ThreeCardHand::Straight,
+            ),
+            (
+                [(Rank::Two, Suit::Hearts), (Rank::King, Suit::Hearts)],
+                ThreeCardHand::Flush,
+            ),
+        ];
+        for (cards, expected) in cases {
+            let hand = make_hand(&cards);
+            assert_eq!(evaluate_twenty_one_plus_3(&hand, upcard), Some(expected));
+        }
+
+        let nothing = make_hand(&[(Rank::Two, Suit::Clubs), (Rank::King, Suit::Hearts)]);
+        assert_eq!(evaluate_twenty_one_plus_3(&nothing, upcard), None);
+    }
+
+    #[test]
+    fn twenty_one_plus_3_ace_straights() {
+        let low = make_hand(&[(Rank::Two, Suit::Clubs), (Rank::Three, Suit::Hearts)]);
+        let high = make_hand(&[(Rank::King, Suit::Clubs), (Rank::Queen, Suit::Hearts)]);
+        let wrap = make_hand(&[(Rank::King, Suit::Clubs), (Rank::Two, Suit::Hearts)]);
+        let ace = Card::new(Rank::Ace, Suit::Spades);
+
+        assert_eq!(
+            evaluate_twenty_one_plus_3(&low, ace),
+            Some(ThreeCardHand::Straight)
+        );
+        assert_eq!(
+            evaluate_twenty_one_plus_3(&high, ace),
+            Some(ThreeCardHand::Straight)
+        );
+        assert_eq!(evaluate_twenty_one_plus_3(&wrap, ace), None);
+    }
+
+    #[test]
+    fn twenty_one_plus_3_uses_paytable() {
+        let mut paytable = TwentyOnePlus3Paytable::default();
+        let hand = make_hand(&[(Rank::Two, Suit::Hearts), (Rank::King, Suit::Hearts)]);
+        let upcard = Card::new(Rank::Nine, Suit::Hearts);
+
+        assert_eq!(
+            calculate_twenty_plus_3_payout(10, &hand, upcard, &paytable),
+            60
+        );
+        paytable.flush = 9;
+        assert_eq!(
+            calculate_twenty_plus_3_payout(10, &hand, upcard, &paytable),
+            100
+        );
+    }
+
+    #[test]
+    fn royal_match() {
+        let paytable = RoyalMatchPaytable::default();
+        let royal = make_hand(&[(Rank::King, Suit::Hearts), (Rank::Queen, Suit::Hearts)]);
+        let suited = make_hand(&[(Rank::Two, Suit::Hearts), (Rank::Nine, Suit::Hearts)]);
+        let offsuit = make_hand(&[(Rank::King, Suit::Hearts), (Rank::Queen, Suit::Spades)]);
+
+        assert_eq!(calculate_royal_match_payout(10, &royal, &paytable), 110);
+        assert_eq!(calculate_royal_match_payout(10, &suited, &paytable), 40);
+        assert_eq!(calculate_royal_match_payout(10, &offsuit, &paytable), 0);
+    }
+
+    #[test]
+    fn lucky_ladies() {
+        let paytable = LuckyLadiesPaytable::default();
+        let no_bj = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+        let bj = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+
+        let queens = make_hand(&[(Rank::Queen, Suit::Hearts), (Rank::Queen, Suit::Hearts)]);
+        let matched = make_hand(&[(Rank::King, Suit::Clubs), (Rank::King, Suit::Clubs)]);
+        let suited = make_hand(&[(Rank::King, Suit::Clubs), (Rank::Ten, Suit::Clubs)]);
+        let any = make_hand(&[(Rank::Ace, Suit::Clubs), (Rank::Nine, Suit::Hearts)]);
+        let nineteen = make_hand(&[(Rank::King, Suit::Clubs), (Rank::Nine, Suit::Hearts)]);
+
+        assert_eq!(
+            calculate_lucky_ladies_payout(1, &queens, &bj, &paytable),
+            1001
+        );
+        assert_eq!(
+            calculate_lucky_ladies_payout(1, &queens, &no_bj, &paytable),
+            201
+        );
+        assert_eq!(
+            calculate_lucky_ladies_payout(1, &matched, &no_bj, &paytable),
+            26
+        );
+        assert_eq!(
+            calculate_lucky_ladies_payout(1, &suited, &no_bj, &paytable),
+            11
+        );
+        assert_eq!(calculate_lucky_ladies_payout(1, &any, &no_bj, &paytable), 5);
+        assert_eq!(
+            calculate_lucky_ladies_payout(1, &nineteen, &no_bj, &paytable),
+            0
+        );
+    }
+
+    #[test]
+    fn bust_it_by_card_count() {
+        let paytable = BustItPaytable::default();
+        let three = make_hand(&[
+            (Rank::Ten, Suit::Spades),
+            (Rank::Six, Suit::Hearts),
+            (Rank::King, Suit::Clubs),
+        ]);
+        let six = make_hand(&[
+            (Rank::Two, Suit::Spades),
+            (Rank::Two, Suit::Hearts),
+            (Rank::Three, Suit::Clubs),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Five, Suit::Clubs),
+            (Rank::Ten, Suit::Clubs),
+        ]);
+        let standing = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Seven, Suit::Hearts)]);
+
+        assert_eq!(calculate_bust_it_payout(10, &three, &paytable), 20);
+        assert_eq!(calculate_bust_it_payout(10, &six, &paytable), 510);
+        assert_eq!(calculate_bust_it_payout(10, &standing, &paytable), 0);
+    }
+
+    #[test]
+    fn super_7s() {
+        let paytable = Super7sPaytable::default();
+        let seven = |suit| Card::new(Rank::Seven, suit);
+        let four = Card::new(Rank::Four, Suit::Clubs);
+
+        let pay = |cards: &[Card]| calculate_super_7s_payout(1, cards, &paytable);
+        assert_eq!(pay(&[four, seven(Suit::Clubs), seven(Suit::Clubs)]), 0);
+        assert_eq!(pay(&[seven(Suit::Clubs), four]), 4);
+        assert_eq!(pay(&[seven(Suit::Clubs), seven(Suit::Hearts), four]), 51);
+        assert_eq!(pay(&[seven(Suit::Clubs), seven(Suit::Clubs)]), 101);
+        let three = [seven(Suit::Clubs), seven(Suit::Hearts), seven(Suit::Clubs)];
+        assert_eq!(pay(&three), 501);
+        let suited = [seven(Suit::Clubs); 3];
+        assert_eq!(pay(&suited), 5001);
+    }
+
+    #[test]
+    fn lucky_lucky() {
+        let paytable = LuckyLuckyPaytable::default();
+        let pay = |cards: &[(Rank, Suit)], up: (Rank, Suit)| {
+            calculate_lucky_lucky_payout(1, &make_hand(cards), up.into(), &paytable)
+        };
+
+        let sevens = [(Rank::Seven, Suit::Clubs), (Rank::Seven, Suit::Clubs)];
+        assert_eq!(pay(&sevens, (Rank::Seven, Suit::Clubs)), 201);
+        assert_eq!(pay(&sevens, (Rank::Seven, Suit::Hearts)), 51);
+
+        let six_eight = [(Rank::Eight, Suit::Clubs), (Rank::Six, Suit::Clubs)];
+        assert_eq!(pay(&six_eight, (Rank::Seven, Suit::Clubs)), 101);
+        assert_eq!(pay(&six_eight, (Rank::Seven, Suit::Hearts)), 31);
+
+        let ace_king = [(Rank::Ace, Suit::Clubs), (Rank::King, Suit::Clubs)];
+        assert_eq!(pay(&ace_king, (Rank::Ten, Suit::Clubs)), 16);
+        assert_eq!(pay(&ace_king, (Rank::Ten, Suit::Hearts)), 4);
+        assert_eq!(pay(&ace_king, (Rank::Nine, Suit::Hearts)), 3);
+        assert_eq!(pay(&ace_king, (Rank::Eight, Suit::Hearts)), 3);
+        assert_eq!(pay(&ace_king, (Rank::Seven, Suit::Hearts)), 0);
+    }
+
+    #[test]
+    fn pair_squared() {
+        let paytable = PairSquaredPaytable::default();
+        let suited = make_hand(&[(Rank::Five, Suit::Clubs), (Rank::Five, Suit::Clubs)]);
+        let unsuited = make_hand(&[(Rank::Five, Suit::Clubs), (Rank::Five, Suit::Spades)]);
+        let none = make_hand(&[(Rank::Five, Suit::Clubs), (Rank::Six, Suit::Clubs)]);
+
+        assert_eq!(calculate_pair_squared_payout(2, &suited, &paytable), 52);
+        assert_eq!(calculate_pair_squared_payout(2, &unsuited, &paytable), 22);
+        assert_eq!(calculate_pair_squared_payout(2, &none, &paytable), 0);
+    }
+
+    #[test]
+    fn blackjack_spin_needs_natural() {
+        let paytable = BlackjackSpinPaytable {
+            wheel: vec![3, 10, 100],
+        };
+        let natural = make_hand(&[(Rank::Ace, Suit::Clubs), (Rank::King, Suit::Clubs)]);
+        let twenty = make_hand(&[(Rank::Queen, Suit::Clubs), (Rank::King, Suit::Clubs)]);
+
+        assert_eq!(
+            calculate_blackjack_spin_payout(1, &natural, 0, &paytable),
+            4
+        );
+        assert_eq!(
+            calculate_blackjack_spin_payout(1, &natural, 5, &paytable),
+            101
+        );
+        assert_eq!(calculate_blackjack_spin_payout(1, &twenty, 5, &paytable), 0);
+    }
+
+    #[test]
+    fn match_the_dealer() {
+        let paytable = MatchTheDealerPaytable::default();
+        let upcard = Card::new(Rank::Nine, Suit::Hearts);
+        let pay = |cards: &[(Rank, Suit)]| {
+            calculate_match_the_dealer_payout(1, &make_hand(cards), upcard, &paytable)
+        };
+
+        assert_eq!(
+            pay(&[(Rank::Nine, Suit::Clubs), (Rank::Two, Suit::Clubs)]),
+            5
+        );
+        assert_eq!(
+            pay(&[(Rank::Nine, Suit::Hearts), (Rank::Two, Suit::Clubs)]),
+            12
+        );
+        assert_eq!(
+            pay(&[(Rank::Nine, Suit::Hearts), (Rank::Nine, Suit::Clubs)]),
+            16
+        );
+        assert_eq!(
+            pay(&[(Rank::Eight, Suit::Hearts), (Rank::Two, Suit::Clubs)]),
+            0
+        );
+    }
+
+    #[test]
+    fn buster_blackjack() {
+        let paytable = BusterBlackjackPaytable::default();
+        let natural = make_hand(&[(Rank::Ace, Suit::Clubs), (Rank::King, Suit::Clubs)]);
+        let twenty = make_hand(&[(Rank::Queen, Suit::Clubs), (Rank::King, Suit::Clubs)]);
+        let six_card_bust = make_hand(&[
+            (Rank::Two, Suit::Spades),
+            (Rank::Two, Suit::Hearts),
+            (Rank::Three, Suit::Clubs),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Five, Suit::Clubs),
+            (Rank::Ten, Suit::Clubs),
+        ]);
+        let three_card_bust = make_hand(&[
+            (Rank::Ten, Suit::Spades),
+            (Rank::Six, Suit::Hearts),
+            (Rank::King, Suit::Clubs),
+        ]);
+        let standing = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Seven, Suit::Hearts)]);
+
+        assert_eq!(
+            calculate_buster_blackjack_payout(1, &twenty, &six_card_bust, &paytable),
+            19
+        );
+        assert_eq!(
+            calculate_buster_blackjack_payout(1, &natural, &six_card_bust, &paytable),
+            101
+        );
+        assert_eq!(
+            calculate_buster_blackjack_payout(1, &twenty, &three_card_bust, &paytable),
+            3
+        );
+        assert_eq!(
+            calculate_buster_blackjack_payout(1, &twenty, &standing, &paytable),
+            0
+        );
+    }
+
+    #[test]
+    fn bet_the_set() {
+        let paytable = BetTheSetPaytable::default();
+        let pair = make_hand(&[(Rank::Four, Suit::Clubs), (Rank::Four, Suit::Clubs)]);
+        let suited = Card::new(Rank::Four, Suit::Clubs);
+        let offsuit = Card::new(Rank::Four, Suit::Hearts);
+        let miss = Card::new(Rank::Five, Suit::Clubs);
+
+        assert_eq!(
+            calculate_bet_the_set_payout(1, &pair, suited, &paytable),
+            101
+        );
+        assert_eq!(
+            calculate_bet_the_set_payout(1, &pair, offsuit, &paytable),
+            11
+        );
+        assert_eq!(calculate_bet_the_set_payout(1, &pair, miss, &paytable), 0);
+    }
+
+    #[test]
+    fn top_3() {
+        let paytable = Top3Paytable::default();
+        let upcard = Card::new(Rank::Nine, Suit::Hearts);
+        let pay = |cards: &[(Rank, Suit)]| {
+            calculate_top_3_payout(1, &make_hand(cards), upcard, &paytable)
+        };
+
+        assert_eq!(
+            pay(&[(Rank::Nine, Suit::Hearts), (Rank::Nine, Suit::Hearts)]),
+            271
+        );
+        assert_eq!(
+            pay(&[(Rank::Seven, Suit::Hearts), (Rank::Eight, Suit::Hearts)]),
+            181
+        );
+        assert_eq!(
+            pay(&[(Rank::Nine, Suit::Clubs), (Rank::Nine, Suit::Spades)]),
+            91
+        );
+        // straights and flushes alone do not pay on Top 3
+        assert_eq!(
+            pay(&[(Rank::Seven, Suit::Clubs), (Rank::Eight, Suit::Hearts)]),
+            0
+        );
+        assert_eq!(
+            pay(&[(Rank::Two, Suit::Hearts), (Rank::King, Suit::Hearts)]),
+            0
+        );
+    }
+
+    #[test]
+    fn hot_3() {
+        let paytable = Hot3Paytable::default();
+        let pay = |cards: &[(Rank, Suit)], up: (Rank, Suit)| {
+            calculate_hot_3_payout(1, &make_hand(cards), up.into(), &paytable)
+        };
+
+        let sevens = [(Rank::Seven, Suit::Clubs), (Rank::Seven, Suit::Hearts)];
+        assert_eq!(pay(&sevens, (Rank::Seven, Suit::Spades)), 101);
+
+        let ace_king = [(Rank::Ace, Suit::Clubs), (Rank::King, Suit::Clubs)];
+        assert_eq!(pay(&ace_king, (Rank::Ten, Suit::Clubs)), 21);
+        assert_eq!(pay(&ace_king, (Rank::Ten, Suit::Hearts)), 5);
+        assert_eq!(pay(&ace_king, (Rank::Nine, Suit::Hearts)), 3);
+        assert_eq!(pay(&ace_king, (Rank::Eight, Suit::Hearts)), 2);
+        assert_eq!(pay(&ace_king, (Rank::Seven, Suit::Hearts)), 0);
+    }
+
+    #[test]
+    fn kings_bounty() {
+        let paytable = KingsBountyPaytable::default();
+        let no_bj = make_hand(&[(Rank::Ten, Suit::Spades), (Rank::Nine, Suit::Hearts)]);
+        let bj = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::King, Suit::Hearts)]);
+        let pay = |cards: &[(Rank, Suit)], dealer: &Hand| {
+            calculate_kings_bounty_payout(1, &make_hand(cards), dealer, &paytable)
+        };
+
+        let spades = [(Rank::King, Suit::Spades), (Rank::King, Suit::Spades)];
+        assert_eq!(pay(&spades, &bj), 1001);
+        assert_eq!(pay(&spades, &no_bj), 201);
+        assert_eq!(
+            pay(
+                &[(Rank::King, Suit::Hearts), (Rank::King, Suit::Hearts)],
+                &no_bj
+            ),
+            101
+        );
+        assert_eq!(
+            pay(
+                &[(Rank::Jack, Suit::Clubs), (Rank::Jack, Suit::Clubs)],
+                &no_bj
+            ),
+            31
+        );
+        assert_eq!(
+            pay(
+                &[(Rank::Jack, Suit::Clubs), (Rank::Ten, Suit::Clubs)],
+                &no_bj
+            ),
+            10
+        );
+        assert_eq!(
+            pay(
+                &[(Rank::Jack, Suit::Clubs), (Rank::Ten, Suit::Hearts)],
+                &no_bj
+            ),
+            5
+        );
+        assert_eq!(
+            pay(
+                &[(Rank::Jack, Suit::Clubs), (Rank::Nine, Suit::Hearts)],
+                &no_bj
+            ),
+            0
+        );
+    }
 }
@@ -0,0 +1,147 @@
+use crate::core::payout::BlackjackPayout;
+use crate::core::rules::{HoleCardRule, Rules, SurrenderRule};
+use crate::error::{ConfigError, ConfigResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Table conditions hosts can pick by name instead of setting every rule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Preset {
+    LasVegasStrip,   // six decks, S17, DAS, late surrender, resplit aces
+    Downtown,        // double deck, H17, DAS
+    AtlanticCity,    // eight decks, S17, DAS, late surrender
+    EuropeanEnhc,    // six decks, S17, no hole card, split once
+    SingleDeckPitch, // one deck, H17, no DAS
+    TouristTrap,     // six decks, H17, naturals paid 6:5
+}
+
+impl Preset {
+    pub const ALL: [Preset; 6] = [
+        Preset::LasVegasStrip,
+        Preset::Downtown,
+        Preset::AtlanticCity,
+        Preset::EuropeanEnhc,
+        Preset::SingleDeckPitch,
+        Preset::TouristTrap,
+    ];
+
+    /// The name used in config files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::LasVegasStrip => "las-vegas-strip",
+            Preset::Downtown => "downtown",
+            Preset::AtlanticCity => "atlantic-city",
+            Preset::EuropeanEnhc => "european-enhc",
+            Preset::SingleDeckPitch => "single-deck-pitch",
+            Preset::TouristTrap => "tourist-trap",
+        }
+    }
+
+    pub fn rules(self) -> Rules {
+        let base = Rules::new();
+        match self {
+            Preset::LasVegasStrip => Rules {
+                split_limit: 3,
+                resplit_aces_allowed: true,
+                surrender: SurrenderRule::Late,
+                ..base
+            },
+            Preset::Downtown => Rules {
+                num_decks: 2,
+                dealer_hits_soft_17: true,
+                split_limit: 3,
+                ..base
+            },
+            Preset::AtlanticCity => Rules {
+                num_decks: 8,
+                split_limit: 3,
+                surrender: SurrenderRule::Late,
+                ..base
+            },
+            Preset::EuropeanEnhc => Rules {
+                hole_card: HoleCardRule::Enhc,
+                ..base
+            },
+            Preset::SingleDeckPitch => Rules {
+                num_decks: 1,
+                dealer_hits_soft_17: true,
+                double_after_split_allowed: false,
+                ..base
+            },
+            Preset::TouristTrap => Rules {
+                blackjack_payout: BlackjackPayout::Vegas,
+                dealer_hits_soft_17: true,
+                split_limit: 3,
+                ..base
+            },
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Accepts the config name in any case, with spaces or underscores for the dashes.
+impl FromStr for Preset {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> ConfigResult<Self> {
+        let wanted = s.trim().to_ascii_lowercase().replace([' ', '_'], "-");
+        Preset::ALL
+            .into_iter()
+            .find(|p| p.name() == wanted)
+            .ok_or_else(|| ConfigError::UnknownPreset(s.to_string()))
+    }
+}
+
+impl Rules {
+    /// Rules for a named preset, as written in a config file.
+    pub fn preset(name: &str) -> ConfigResult<Self> {
+        Ok(name.parse::<Preset>()?.rules())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for preset in Preset::ALL {
+            assert_eq!(preset.name().parse::<Preset>().unwrap(), preset);
+        }
+        assert_eq!(
+            "Las Vegas Strip".parse::<Preset>().unwrap(),
+            Preset::LasVegasStrip
+        );
+        assert_eq!(
+            "SINGLE_DECK_PITCH".parse::<Preset>().unwrap(),
+            Preset::SingleDeckPitch
+        );
+    }
+
+    #[test]
+    fn unknown_preset_rejected() {
+        assert!(matches!(
+            Rules::preset("reno"),
+            Err(ConfigError::UnknownPreset(name)) if name == "reno"
+        ));
+    }
+
+    #[test]
+    fn presets_differ_where_expected() {
+        assert_eq!(Rules::preset("atlantic-city").unwrap().num_decks, 8);
+        assert_eq!(
+            Rules::preset("european-enhc").unwrap().hole_card,
+            HoleCardRule::Enhc
+        );
+        assert!(matches!(
+            Rules::preset("tourist-trap").unwrap().blackjack_payout,
+            BlackjackPayout::Vegas
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::core::card::{Card, Rank};
+use crate::core::hand::Hand;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum JackpotPrize {
+    Odds(u32),       // fixed "X to 1", paid by the house
+    PoolPercent(u8), // share of the meter at the time of the hit
+}
+
+/// Shared meter for one progressive game. Every wager feeds `contribution_percent` of itself in,
+/// and the meter drops back to `reset_value` after a hit drains it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressivePool {
+    pub value: u64,
+    pub reset_value: u64,
+    pub contribution_percent: u8,
+    // contributions below one credit, in hundredths, so small wagers still move the meter
+    carry: u64,
+}
+
+impl ProgressivePool {
+    pub fn new(reset_value: u64, contribution_percent: u8) -> Self {
+        Self {
+            value: reset_value,
+            reset_value,
+            contribution_percent,
+            carry: 0,
+        }
+    }
+
+    pub fn contribute(&mut self, wager: u32) {
+        self.carry += wager as u64 * self.contribution_percent as u64;
+        self.value += self.carry / 100;
+        self.carry %= 100;
+    }
+
+    /// Returns the stake plus the prize; pool prizes come out of the meter.
+    pub fn pay(&mut self, wager: u32, prize: JackpotPrize) -> u64 {
+        let won = match prize {
+            JackpotPrize::Odds(odds) => wager as u64 * odds as u64,
+            JackpotPrize::PoolPercent(percent) => {
+                let won = self.value * percent.min(100) as u64 / 100;
+                self.value = (self.value - won).max(self.reset_value);
+                won
+            }
+        };
+        wager as u64 + won
+    }
+}
+
+/// Meters keyed by the id of the side bet that feeds them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jackpots {
+    pools: BTreeMap<String, ProgressivePool>,
+}
+
+impl Jackpots {
+    pub fn get(&self, id: &str) -> Option<&ProgressivePool> {
+        self.pools.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut ProgressivePool> {
+        self.pools.get_mut(id)
+    }
+
+    /// Opens a meter for `id` unless one is already running.
+    pub fn open(&mut self, id: &str, reset_value: u64, contribution_percent: u8) {
+        self.pools
+            .entry(id.to_string())
+            .or_insert_with(|| ProgressivePool::new(reset_value, contribution_percent));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ProgressivePool)> {
+        self.pools.iter().map(|(id, pool)| (id.as_str(), pool))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Caribbean21Hand {
+    ThreeCard21,
+    Blackjack,
+    SuitedBlackjack,
+    SuitedBlackjackAceUp,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Caribbean21Paytable {
+    pub three_card_21: JackpotPrize,
+    pub blackjack: JackpotPrize,
+    pub suited_blackjack: JackpotPrize,
+    pub suited_blackjack_ace_up: JackpotPrize,
+}
+
+impl Default for Caribbean21Paytable {
+    fn default() -> Self {
+        Self {
+            three_card_21: JackpotPrize::Odds(3),
+            blackjack: JackpotPrize::Odds(5),
+            suited_blackjack: JackpotPrize::PoolPercent(10),
+            suited_blackjack_ace_up: JackpotPrize::PoolPercent(100),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Blazing7sHand {
+    OneSeven,
+    TwoSevens,
+    ThreeSevens,
+    ThreeSevensSameColor,
+    ThreeSuitedSevens,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blazing7sPaytable {
+    pub one_seven: JackpotPrize,
+    pub two_sevens: JackpotPrize,
+    pub three_sevens: JackpotPrize,
+    pub three_sevens_same_color: JackpotPrize,
+    pub three_suited_sevens: JackpotPrize,
+}
+
+impl Default for Blazing7sPaytable {
+    fn default() -> Self {
+        Self {
+            one_seven: JackpotPrize::Odds(2),
+            two_sevens: JackpotPrize::Odds(25),
+            three_sevens: JackpotPrize::Odds(500),
+            three_sevens_same_color: JackpotPrize::PoolPercent(10),
+            three_suited_sevens: JackpotPrize::PoolPercent(100),
+        }
+    }
+}
+
+/// Player's first two cards plus the dealer's upcard.
+pub fn evaluate_caribbean_21(player_hand: &Hand, dealer_upcard: Card) -> Option<Caribbean21Hand> {
+    let [a, b, ..] = player_hand.cards() else {
+        return None;
+    };
+
+    if player_hand.is_blackjack() {
+        if a.suit != b.suit {
+            return Some(Caribbean21Hand::Blackjack);
+        }
+        if dealer_upcard.rank == Rank::Ace && dealer_upcard.suit == a.suit {
+            return Some(Caribbean21Hand::SuitedBlackjackAceUp);
+        }
+        return Some(Caribbean21Hand::SuitedBlackjack);
+    }
+
+    let mut three = Hand::new();
+    for card in [*a, *b, dealer_upcard] {
+        three.add_card(card);
+    }
+    (three.value() == 21).then_some(Caribbean21Hand::ThreeCard21)
+}
+
+/// Sevens among the player's first two cards and the dealer's upcard.
+pub fn evaluate_blazing_7s(player_hand: &Hand, dealer_upcard: Card) -> Option<Blazing7sHand> {
+    let [a, b, ..] = player_hand.cards() else {
+        return None;
+    };
+
+    let cards = [*a, *b, dealer_upcard];
+    let sevens = cards.iter().filter(|c| c.rank == Rank::Seven).count();
+    Some(match sevens {
+        0 => return None,
+        1 => Blazing7sHand::OneSeven,
+        2 => Blazing7sHand::TwoSevens,
+        _ if cards.iter().all(|c| c.suit == a.suit) => Blazing7sHand::ThreeSuitedSevens,
+        _ if cards.iter().all(|c| c.suit.is_red() == a.suit.is_red()) => {
+            Blazing7sHand::ThreeSevensSameColor
+        }
+        _ => Blazing7sHand::ThreeSevens,
+    })
+}
+
+pub fn caribbean_21_prize(
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &Caribbean21Paytable,
+) -> Option<JackpotPrize> {
+    Some(match evaluate_caribbean_21(player_hand, dealer_upcard)? {
+        Caribbean21Hand::ThreeCard21 => paytable.three_card_21,
+        Caribbean21Hand::Blackjack => paytable.blackjack,
+        Caribbean21Hand::SuitedBlackjack => paytable.suited_blackjack,
+        Caribbean21Hand::SuitedBlackjackAceUp => paytable.suited_blackjack_ace_up,
+    })
+}
+
+pub fn blazing_7s_prize(
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &Blazing7sPaytable,
+) -> Option<JackpotPrize> {
+    Some(match evaluate_blazing_7s(player_hand, dealer_upcard)? {
+        Blazing7sHand::OneSeven => paytable.one_seven,
+        Blazing7sHand::TwoSevens => paytable.two_sevens,
+        Blazing7sHand::ThreeSevens => paytable.three_sevens,
+        Blazing7sHand::ThreeSevensSameColor => paytable.three_sevens_same_color,
+        Blazing7sHand::ThreeSuitedSevens => paytable.three_suited_sevens,
+    })
+}
+
+pub fn calculate_caribbean_21_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &Caribbean21Paytable,
+    pool: &mut ProgressivePool,
+) -> u64 {
+    caribbean_21_prize(player_hand, dealer_upcard, paytable).map_or(0, |p| pool.pay(side_bet, p))
+}
+
+pub fn calculate_blazing_7s_payout(
+    side_bet: u32,
+    player_hand: &Hand,
+    dealer_upcard: Card,
+    paytable: &Blazing7sPaytable,
+    pool: &mut ProgressivePool,
+) -> u64 {
+    blazing_7s_prize(player_hand, dealer_upcard, paytable).map_or(0, |p| pool.pay(side_bet, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::Suit;
+
+    fn make_hand(cards: &[(Rank, Suit)]) -> Hand {
+        let mut hand = Hand::new();
+        for &(rank, suit) in cards {
+            hand.add_card(Card::new(rank, suit));
+        }
+        hand
+    }
+
+    #[test]
+    fn pool_accumulates_fractions() {
+        let mut pool = ProgressivePool::new(1000, 15);
+        pool.contribute(5);
+        assert_eq!(pool.value, 1000);
+        pool.contribute(5);
+        assert_eq!(pool.value, 1001);
+        for _ in 0..18 {
+            pool.contribute(5);
+        }
+        assert_eq!(pool.value, 1015);
+    }
+
+    #[test]
+    fn pool_percent_prize_drains_meter() {
+        let mut pool = ProgressivePool::new(1000, 10);
+        pool.value = 5000;
+
+        assert_eq!(pool.pay(5, JackpotPrize::PoolPercent(10)), 505);
+        assert_eq!(pool.value, 4500);
+        assert_eq!(pool.pay(5, JackpotPrize::PoolPercent(100)), 4505);
+        assert_eq!(pool.value, 1000);
+    }
+
+    #[test]
+    fn fixed_prize_leaves_meter() {
+        let mut pool = ProgressivePool::new(1000, 10);
+        assert_eq!(pool.pay(5, JackpotPrize::Odds(25)), 130);
+        assert_eq!(pool.value, 1000);
+    }
+
+    #[test]
+    fn caribbean_21_hands() {
+        let ace_up = Card::new(Rank::Ace, Suit::Spades);
+        let ten_up = Card::new(Rank::Ten, Suit::Hearts);
+        let suited = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::Jack, Suit::Spades)]);
+        let offsuit = make_hand(&[(Rank::Ace, Suit::Spades), (Rank::Jack, Suit::Hearts)]);
+        let eleven = make_hand(&[(Rank::Five, Suit::Spades), (Rank::Six, Suit::Hearts)]);
+
+        assert_eq!(
+            evaluate_caribbean_21(&suited, ace_up),
+            Some(Caribbean21Hand::SuitedBlackjackAceUp)
+        );
+        assert_eq!(
+            evaluate_caribbean_21(&suited, ten_up),
+            Some(Caribbean21Hand::SuitedBlackjack)
+        );
+        assert_eq!(
+            evaluate_caribbean_21(&offsuit, ten_up),
+            Some(Caribbean21Hand::Blackjack)
+        );
+        assert_eq!(
+            evaluate_caribbean_21(&eleven, ten_up),
+            Some(Caribbean21Hand::ThreeCard21)
+        );
+        assert_eq!(evaluate_caribbean_21(&eleven, ace_up), None);
+    }
+
+    #[test]
+    fn blazing_7s_hands() {
+        let seven = |suit| (Rank::Seven, suit);
+        let up = |suit| Card::new(Rank::Seven, suit);
+
+        let clubs = make_hand(&[seven(Suit::Clubs), seven(Suit::Clubs)]);
+        let black = make_hand(&[seven(Suit::Clubs), seven(Suit::Spades)]);
+        let one = make_hand(&[seven(Suit::Clubs), (Rank::Two, Suit::Clubs)]);
+        let none = make_hand(&[(Rank::Two, Suit::Clubs), (Rank::Two, Suit::Clubs)]);
+        let two_up = Card::new(Rank::Two, Suit::Clubs);
+
+        assert_eq!(
+            evaluate_blazing_7s(&clubs, up(Suit::Clubs)),
+            Some(Blazing7sHand::ThreeSuitedSevens)
+        );
+        assert_eq!(
+            evaluate_blazing_7s(&black, up(Suit::Clubs)),
+            Some(Blazing7sHand::ThreeSevensSameColor)
+        );
+        assert_eq!(
+            evaluate_blazing_7s(&black, up(Suit::Hearts)),
+            Some(Blazing7sHand::ThreeSevens)
+        );
+        assert_eq!(
+            evaluate_blazing_7s(&clubs, two_up),
+            Some(Blazing7sHand::TwoSevens)
+        );
+        assert_eq!(
+            evaluate_blazing_7s(&one, two_up),
+            Some(Blazing7sHand::OneSeven)
+        );
+        assert_eq!(evaluate_blazing_7s(&none, two_up), None);
+    }
+
+    #[test]
+    fn blazing_7s_jackpot_hit() {
+        let paytable = Blazing7sPaytable::default();
+        let mut pool = ProgressivePool::new(1000, 10);
+        pool.value = 2500;
+        let hand = make_hand(&[(Rank::Seven, Suit::Clubs), (Rank::Seven, Suit::Clubs)]);
+        let upcard = Card::new(Rank::Seven, Suit::Clubs);
+
+        assert_eq!(
+            calculate_blazing_7s_payout(1, &hand, upcard, &paytable, &mut pool),
+            2501
+        );
+        assert_eq!(pool.value, 1000);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::core::hand::Hand;
+use serde::{Deserialize, Serialize};
+
+/// Pontoon hand strength, weakest first. Equal ranks are ties, which the banker wins.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PontoonRank {
+    Bust,
+    Total(u8),
+    FiveCardTrick, // five cards without busting, whatever the total
+    Pontoon,       // a natural: two-card 21
+}
+
+pub const FIVE_CARD_TRICK: usize = 5;
+
+impl PontoonRank {
+    pub fn of(hand: &Hand) -> Self {
+        if hand.is_bust() {
+            PontoonRank::Bust
+        } else if hand.is_blackjack() {
+            PontoonRank::Pontoon
+        } else if hand.cards().len() >= FIVE_CARD_TRICK {
+            PontoonRank::FiveCardTrick
+        } else {
+            PontoonRank::Total(hand.value())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::{Card, Rank, Suit};
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(Card::new(rank, Suit::Clubs));
+        }
+        hand
+    }
+
+    #[test]
+    fn ranks_order() {
+        let pontoon = PontoonRank::of(&hand(&[Rank::Ace, Rank::King]));
+        let trick = PontoonRank::of(&hand(&[
+            Rank::Two,
+            Rank::Three,
+            Rank::Two,
+            Rank::Four,
+            Rank::Ace,
+        ]));
+        let twenty_one = PontoonRank::of(&hand(&[Rank::Seven, Rank::Seven, Rank::Seven]));
+        let twenty = PontoonRank::of(&hand(&[Rank::King, Rank::Queen]));
+        let bust = PontoonRank::of(&hand(&[Rank::King, Rank::Queen, Rank::Five]));
+
+        assert_eq!(trick, PontoonRank::FiveCardTrick);
+        assert!(pontoon > trick);
+        assert!(trick > twenty_one);
+        assert!(twenty_one > twenty);
+        assert!(twenty > bust);
+    }
+
+    #[test]
+    fn bust_five_cards_is_no_trick() {
+        let busted = hand(&[Rank::Five, Rank::Five, Rank::Five, Rank::Five, Rank::Five]);
+        assert_eq!(PontoonRank::of(&busted), PontoonRank::Bust);
+    }
+}
@@ -1,67 +1,420 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-use crate::core::payout::BlackjackPayout;
+use crate::core::card::{Card, DeckKind, Rank};
+use crate::core::payout::{
+    BetTheSetPaytable, BlackjackPayout, BlackjackSpinPaytable, BustItPaytable,
+    BusterBlackjackPaytable, Hot3Paytable, KingsBountyPaytable, LuckyLadiesPaytable,
+    LuckyLuckyPaytable, MatchTheDealerPaytable, PairSquaredPaytable, PayoutRatio,
+    PerfectPairsPaytable, RoundingPolicy, RoyalMatchPaytable, Spanish21Paytable, Super7sPaytable,
+    Top3Paytable, TwentyOnePlus3Paytable,
+};
+use crate::core::progressive::{Blazing7sPaytable, Caribbean21Paytable};
+use crate::core::side_bet::builtins;
+use crate::error::{ConfigError, ConfigResult};
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoleCardRule {
+    Peek, // US: hole card dealt up front, dealer checks for blackjack under an Ace or ten
+    Enhc, // European no hole card: a dealer natural takes doubles and splits too
+    Obo,  // no hole card, but only original bets are lost to a dealer natural
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Classic,
+    Spanish21, // no tens in the shoe, player 21 always wins, bonuses for long and 6-7-8 / 7-7-7 21s
+    Switch,    // two boxes per seat with swappable second cards, naturals 1:1, dealer 22 pushes
+    DoubleExposure, // both dealer cards face up, dealer wins ties, naturals 1:1
+    Pontoon,   // British: twist/stick/buy, five-card tricks, banker wins ties, cards hidden
+    FreeBet,   // free doubles on hard 9-11 and free splits except tens, dealer 22 pushes
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SurrenderRule {
+    None,
+    Late,           // only after the dealer has checked for blackjack
+    Early,          // before the dealer checks, against any upcard
+    EarlyExceptAce, // early against a ten, late against an Ace
+}
+
+impl SurrenderRule {
+    pub fn allows_early(self, upcard: Card) -> bool {
+        match self {
+            SurrenderRule::Early => true,
+            SurrenderRule::EarlyExceptAce => upcard.rank != Rank::Ace,
+            SurrenderRule::None | SurrenderRule::Late => false,
+        }
+    }
+
+    pub fn allows_late(self) -> bool {
+        self != SurrenderRule::None
+    }
+}
+
+/// How the cards get back into play.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShuffleMode {
+    CutCard { penetration_percent: u8 }, // share of the shoe dealt before the reshuffle
+    Continuous { buffer: u16 },          // CSM: cards queued ahead of each round's discards
+}
+
+impl Default for ShuffleMode {
+    fn default() -> Self {
+        ShuffleMode::CutCard {
+            penetration_percent: 75,
+        }
+    }
+}
+
+impl fmt::Display for ShuffleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShuffleMode::CutCard {
+                penetration_percent,
+            } => write!(f, "cut:{penetration_percent}"),
+            ShuffleMode::Continuous { buffer } => write!(f, "csm:{buffer}"),
+        }
+    }
+}
+
+/// `cut:PERCENT` for a hand-shuffled shoe, `csm:BUFFER` for a continuous shuffler.
+impl FromStr for ShuffleMode {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> ConfigResult<Self> {
+        let invalid = || ConfigError::Other(format!("invalid shuffle mode: {s}"));
+        let (kind, value) = s.trim().split_once(':').ok_or_else(invalid)?;
+        match kind.to_ascii_lowercase().as_str() {
+            "cut" => Ok(ShuffleMode::CutCard {
+                penetration_percent: value.parse().map_err(|_| invalid())?,
+            }),
+            "csm" => Ok(ShuffleMode::Continuous {
+                buffer: value.parse().map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Fields missing from a saved or hand-written table take their [`Rules::new`] values.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Rules {
+    pub variant: Variant,
     pub min_bet: u32,
     pub max_bet: u32,
     pub starting_credits: u32,
     pub blackjack_payout: BlackjackPayout,
+    pub payout_rounding: RoundingPolicy,
     pub num_decks: u8,
+    pub shuffle: ShuffleMode,
     pub split_limit: u8,
     pub dealer_hits_soft_17: bool,
-    pub surrender_allowed: bool,
+    pub hole_card: HoleCardRule,
+    pub surrender: SurrenderRule,
     pub resplit_aces_allowed: bool,
     pub hit_split_aces_allowed: bool,
     pub double_after_split_allowed: bool,
+    pub double_down_rescue: bool, // a doubled hand may still be surrendered, losing the original bet
+    pub spanish_21_paytable: Spanish21Paytable,
 
     // core side bets
     pub insurance_enabled: bool,
     pub perfect_pairs_enabled: bool,
     pub twenty_one_plus_3_enabled: bool,
     pub royal_match_enabled: bool,
+    pub perfect_pairs_paytable: Option<PerfectPairsPaytable>,
+    pub twenty_one_plus_3_paytable: Option<TwentyOnePlus3Paytable>,
+    pub royal_match_paytable: Option<RoyalMatchPaytable>,
     // high-variance side bets
-    // pub lucky_ladies_enabled: bool,
-    // pub bust_it_enabled: bool,
-    // pub super_7s_enabled: bool,
-    // pub lucky_lucky_enabled: bool,
-    // pub pair_squared_enabled: bool,
-
+    pub lucky_ladies_enabled: bool,
+    pub bust_it_enabled: bool,
+    pub super_7s_enabled: bool,
+    pub lucky_lucky_enabled: bool,
+    pub pair_squared_enabled: bool,
+    pub lucky_ladies_paytable: Option<LuckyLadiesPaytable>,
+    pub bust_it_paytable: Option<BustItPaytable>,
+    pub super_7s_paytable: Option<Super7sPaytable>,
+    pub lucky_lucky_paytable: Option<LuckyLuckyPaytable>,
+    pub pair_squared_paytable: Option<PairSquaredPaytable>,
     // specialty side bets
-    // pub blackjack_spin_enabled: bool,
-    // pub match_the_dealer_enabled: bool,
-    // pub buster_blackjack_enabled: bool,
-    // pub bet_the_set_enabled: bool,
-    // pub top_3_enabled: bool,
-    // pub hot_3_enabled: bool,
-    // pub kings_bounty_enabled: bool,
-
+    pub blackjack_spin_enabled: bool,
+    pub match_the_dealer_enabled: bool,
+    pub buster_blackjack_enabled: bool,
+    pub bet_the_set_enabled: bool,
+    pub top_3_enabled: bool,
+    pub hot_3_enabled: bool,
+    pub kings_bounty_enabled: bool,
+    pub blackjack_spin_paytable: Option<BlackjackSpinPaytable>,
+    pub match_the_dealer_paytable: Option<MatchTheDealerPaytable>,
+    pub buster_blackjack_paytable: Option<BusterBlackjackPaytable>,
+    pub bet_the_set_paytable: Option<BetTheSetPaytable>,
+    pub top_3_paytable: Option<Top3Paytable>,
+    pub hot_3_paytable: Option<Hot3Paytable>,
+    pub kings_bounty_paytable: Option<KingsBountyPaytable>,
     // progressive jackpots
-    // pub caribbean_21_enabled: bool,
-    // pub blazing_7s_enabled: bool,
+    pub caribbean_21_enabled: bool,
+    pub blazing_7s_enabled: bool,
+    pub caribbean_21_paytable: Option<Caribbean21Paytable>,
+    pub blazing_7s_paytable: Option<Blazing7sPaytable>,
+    pub jackpot_seed: u64,                // meter value after a reset
+    pub jackpot_contribution_percent: u8, // share of each progressive wager fed to the meter
 }
 
 impl Rules {
     pub fn new() -> Self {
         Self {
+            variant: Variant::Classic,
             min_bet: 10,
             max_bet: 500,
             starting_credits: 1000,
             blackjack_payout: BlackjackPayout::Standard,
+            payout_rounding: RoundingPolicy::Floor,
             num_decks: 6,
+            shuffle: ShuffleMode::default(),
             split_limit: 1,
             dealer_hits_soft_17: false,
-            surrender_allowed: false,
+            hole_card: HoleCardRule::Peek,
+            surrender: SurrenderRule::None,
             resplit_aces_allowed: false,
             hit_split_aces_allowed: false,
             double_after_split_allowed: true,
+            double_down_rescue: false,
+            spanish_21_paytable: Spanish21Paytable::default(),
             insurance_enabled: false,
             perfect_pairs_enabled: false,
             twenty_one_plus_3_enabled: false,
             royal_match_enabled: false,
+            perfect_pairs_paytable: Some(PerfectPairsPaytable::default()),
+            twenty_one_plus_3_paytable: Some(TwentyOnePlus3Paytable::default()),
+            royal_match_paytable: Some(RoyalMatchPaytable::default()),
+            lucky_ladies_enabled: false,
+            bust_it_enabled: false,
+            super_7s_enabled: false,
+            lucky_lucky_enabled: false,
+            pair_squared_enabled: false,
+            lucky_ladies_paytable: Some(LuckyLadiesPaytable::default()),
+            bust_it_paytable: Some(BustItPaytable::default()),
+            super_7s_paytable: Some(Super7sPaytable::default()),
+            lucky_lucky_paytable: Some(LuckyLuckyPaytable::default()),
+            pair_squared_paytable: Some(PairSquaredPaytable::default()),
+            blackjack_spin_enabled: false,
+            match_the_dealer_enabled: false,
+            buster_blackjack_enabled: false,
+            bet_the_set_enabled: false,
+            top_3_enabled: false,
+            hot_3_enabled: false,
+            kings_bounty_enabled: false,
+            blackjack_spin_paytable: Some(BlackjackSpinPaytable::default()),
+            match_the_dealer_paytable: Some(MatchTheDealerPaytable::default()),
+            buster_blackjack_paytable: Some(BusterBlackjackPaytable::default()),
+            bet_the_set_paytable: Some(BetTheSetPaytable::default()),
+            top_3_paytable: Some(Top3Paytable::default()),
+            hot_3_paytable: Some(Hot3Paytable::default()),
+            kings_bounty_paytable: Some(KingsBountyPaytable::default()),
+            caribbean_21_enabled: false,
+            blazing_7s_enabled: false,
+            caribbean_21_paytable: Some(Caribbean21Paytable::default()),
+            blazing_7s_paytable: Some(Blazing7sPaytable::default()),
+            jackpot_seed: 10_000,
+            jackpot_contribution_percent: 10,
+        }
+    }
+
+    /// Six-deck Spanish 21: H17, late surrender, resplit and double after split, rescue.
+    pub fn spanish_21() -> Self {
+        Self {
+            variant: Variant::Spanish21,
+            dealer_hits_soft_17: true,
+            split_limit: 3,
+            resplit_aces_allowed: true,
+            surrender: SurrenderRule::Late,
+            double_down_rescue: true,
+            ..Self::new()
+        }
+    }
+
+    /// Six-deck Blackjack Switch: H17, double after split, no surrender.
+    pub fn blackjack_switch() -> Self {
+        Self {
+            variant: Variant::Switch,
+            dealer_hits_soft_17: true,
+            split_limit: 3,
+            ..Self::new()
+        }
+    }
+
+    /// Eight-deck Double Exposure: H17, resplit to four hands, no insurance to offer.
+    pub fn double_exposure() -> Self {
+        Self {
+            variant: Variant::DoubleExposure,
+            num_decks: 8,
+            dealer_hits_soft_17: true,
+            split_limit: 3,
+            ..Self::new()
+        }
+    }
+
+    /// Six-deck Free Bet: H17, resplit to four hands.
+    pub fn free_bet() -> Self {
+        Self {
+            variant: Variant::FreeBet,
+            dealer_hits_soft_17: true,
+            split_limit: 3,
+            ..Self::new()
+        }
+    }
+
+    /// Single-deck Pontoon: banker hits soft 17, a pontoon pays 2:1.
+    pub fn pontoon() -> Self {
+        Self {
+            variant: Variant::Pontoon,
+            num_decks: 1,
+            dealer_hits_soft_17: true,
+            blackjack_payout: BlackjackPayout::Custom {
+                numerator: 2,
+                denominator: 1,
+            },
+            ..Self::new()
         }
     }
+
+    pub fn deck(&self) -> DeckKind {
+        match self.variant {
+            Variant::Spanish21 => DeckKind::Spanish,
+            _ => DeckKind::Standard,
+        }
+    }
+
+    /// Switch and Free Bet pay for their player-friendly rules by pushing on a dealer 22.
+    pub fn dealer_22_pushes(&self) -> bool {
+        matches!(self.variant, Variant::Switch | Variant::FreeBet)
+    }
+
+    /// Double Exposure deals both dealer cards face up.
+    pub fn dealer_cards_exposed(&self) -> bool {
+        self.variant == Variant::DoubleExposure
+    }
+
+    /// The Pontoon banker keeps both cards face down until the players are done.
+    pub fn dealer_cards_concealed(&self) -> bool {
+        self.variant == Variant::Pontoon
+    }
+
+    /// What a winning natural pays. Switch and Double Exposure pay even money whatever the
+    /// table payout says.
+    pub fn natural_payout(&self) -> ConfigResult<PayoutRatio> {
+        match self.variant {
+            Variant::Switch | Variant::DoubleExposure => Ok(PayoutRatio::EVEN_MONEY),
+            _ => self.blackjack_payout.ratio(),
+        }
+    }
+
+    /// Smallest bet that every payout at the table settles without a fraction: the natural,
+    /// and half back on a surrender where surrender is offered.
+    pub fn exact_bet_unit(&self) -> u32 {
+        let natural = self.natural_payout().map_or(1, |r| r.unit());
+        let half = PayoutRatio::ONE_TO_TWO.unit();
+        if self.surrender == SurrenderRule::None || natural.is_multiple_of(half) {
+            natural
+        } else {
+            natural * half
+        }
+    }
+
+    /// Seats play two boxes at once only in Switch.
+    pub fn boxes_per_seat(&self) -> usize {
+        match self.variant {
+            Variant::Switch => 2,
+            _ => 1,
+        }
+    }
+
+    /// Checks the rules hang together, reporting every problem at once as
+    /// [`ConfigError::InvalidRules`].
+    pub fn validate(&self) -> ConfigResult<()> {
+        let mut errors = Vec::new();
+
+        if self.num_decks == 0 {
+            errors.push(ConfigError::InvalidDecks(self.num_decks));
+        }
+        if self.min_bet == 0 || self.min_bet > self.max_bet {
+            errors.push(ConfigError::InvalidBetLimits {
+                min: self.min_bet,
+                max: self.max_bet,
+            });
+        }
+        if let Err(e) = self.blackjack_payout.ratio() {
+            errors.push(e);
+        }
+        if let Err(e) = self.payout_rounding.validate() {
+            errors.push(e);
+        }
+        let total_cards = self.num_decks as usize * self.deck().size();
+        match self.shuffle {
+            ShuffleMode::CutCard {
+                penetration_percent: 0,
+            } => errors.push(ConfigError::InvalidCutPosition(0, total_cards)),
+            ShuffleMode::CutCard {
+                penetration_percent,
+            } if penetration_percent > 100 => {
+                errors.push(ConfigError::InvalidPercentage(penetration_percent));
+            }
+            ShuffleMode::Continuous { buffer } if buffer as usize >= total_cards => {
+                errors.push(ConfigError::InvalidCutPosition(
+                    buffer as usize,
+                    total_cards,
+                ));
+            }
+            _ => {}
+        }
+        if self.jackpot_contribution_percent > 100 {
+            errors.push(ConfigError::InvalidPercentage(
+                self.jackpot_contribution_percent,
+            ));
+        }
+
+        if self.resplit_aces_allowed && self.split_limit < 2 {
+            errors.push(ConfigError::ConflictingRules(format!(
+                "resplitting aces needs a split limit of at least 2, not {}",
+                self.split_limit
+            )));
+        }
+        if self.split_limit == 0 && self.hit_split_aces_allowed {
+            errors.push(ConfigError::ConflictingRules(
+                "hitting split aces with splitting disabled".into(),
+            ));
+        }
+        if self.split_limit == 0 && self.double_after_split_allowed {
+            errors.push(ConfigError::ConflictingRules(
+                "doubling after split with splitting disabled".into(),
+            ));
+        }
+        if self.double_down_rescue && !self.surrender.allows_late() {
+            errors.push(ConfigError::ConflictingRules(
+                "double-down rescue without surrender".into(),
+            ));
+        }
+
+        for builtin in builtins(self) {
+            if builtin.enabled && builtin.bet.is_none() {
+                errors.push(ConfigError::MissingPaytable(builtin.id.into()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::InvalidRules(errors))
+        }
+    }
+
+    pub fn dealer_has_hole_card(&self) -> bool {
+        self.hole_card == HoleCardRule::Peek
+    }
 }
 
 impl Default for Rules {
@@ -69,3 +422,102 @@ impl Default for Rules {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::side_bet::LUCKY_LADIES;
+
+    #[test]
+    fn presets_are_valid() {
+        for rules in [
+            Rules::new(),
+            Rules::spanish_21(),
+            Rules::blackjack_switch(),
+            Rules::double_exposure(),
+            Rules::free_bet(),
+            Rules::pontoon(),
+        ] {
+            rules.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn every_violation_reported() {
+        let rules = Rules {
+            num_decks: 0,
+            min_bet: 100,
+            max_bet: 10,
+            resplit_aces_allowed: true,
+            lucky_ladies_enabled: true,
+            lucky_ladies_paytable: None,
+            ..Rules::new()
+        };
+        let Err(ConfigError::InvalidRules(errors)) = rules.validate() else {
+            panic!("rules should be rejected");
+        };
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(errors[0], ConfigError::InvalidDecks(0)));
+        assert!(matches!(
+            errors[1],
+            ConfigError::InvalidBetLimits { min: 100, max: 10 }
+        ));
+        assert!(matches!(errors[2], ConfigError::ConflictingRules(_)));
+        assert!(matches!(&errors[3], ConfigError::MissingPaytable(id) if id == LUCKY_LADIES));
+    }
+
+    #[test]
+    fn shuffle_modes() {
+        assert_eq!(
+            "csm:20".parse::<ShuffleMode>().unwrap(),
+            ShuffleMode::Continuous { buffer: 20 }
+        );
+        assert_eq!(
+            "cut:85".parse::<ShuffleMode>().unwrap().to_string(),
+            "cut:85"
+        );
+        assert!("shuffle".parse::<ShuffleMode>().is_err());
+        assert!("csm:lots".parse::<ShuffleMode>().is_err());
+
+        let invalid = |shuffle| Rules {
+            shuffle,
+            ..Rules::new()
+        };
+        assert!(
+            invalid(ShuffleMode::CutCard {
+                penetration_percent: 0
+            })
+            .validate()
+            .is_err()
+        );
+        assert!(
+            invalid(ShuffleMode::CutCard {
+                penetration_percent: 101
+            })
+            .validate()
+            .is_err()
+        );
+        assert!(
+            invalid(ShuffleMode::Continuous { buffer: 312 })
+                .validate()
+                .is_err()
+        );
+        invalid(ShuffleMode::Continuous { buffer: 0 })
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn no_splitting_conflicts() {
+        let rules = Rules {
+            split_limit: 0,
+            hit_split_aces_allowed: true,
+            ..Rules::new()
+        };
+        let Err(ConfigError::InvalidRules(errors)) = rules.validate() else {
+            panic!("rules should be rejected");
+        };
+        // hitting split aces and the default double after split
+        assert_eq!(errors.len(), 2);
+    }
+}
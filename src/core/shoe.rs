@@ -1,7 +1,7 @@
-use crate::core::card::Card;
+use crate::core::card::{Card, DeckKind};
 use crate::error::{ConfigError, ConfigResult, GameError, GameResult};
-use rand::SeedableRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 
@@ -12,19 +12,30 @@ pub struct Shoe {
     cut_position: usize,
     num_decks: u8,
     rng: ChaCha8Rng,
+    #[serde(default)]
+    csm_buffer: Option<usize>, // continuous shuffler: cards queued ahead of returned discards
 }
 
 impl Shoe {
     pub fn new(num_decks: u8, cut_position: usize, seed: Option<u64>) -> ConfigResult<Self> {
+        Self::with_deck(DeckKind::Standard, num_decks, cut_position, seed)
+    }
+
+    pub fn with_deck(
+        deck: DeckKind,
+        num_decks: u8,
+        cut_position: usize,
+        seed: Option<u64>,
+    ) -> ConfigResult<Self> {
         if num_decks == 0 {
             return Err(ConfigError::InvalidDecks(num_decks));
         }
 
-        let total_cards = num_decks as usize * 52;
+        let total_cards = num_decks as usize * deck.size();
 
         let mut cards = Vec::with_capacity(total_cards);
         for _ in 0..num_decks {
-            cards.extend(Card::standard_deck());
+            cards.extend(deck.cards());
         }
 
         let mut rng = match seed {
@@ -44,9 +55,51 @@ impl Shoe {
             top_position: 0,
             cut_position,
             rng,
+            csm_buffer: None,
         })
     }
 
+    /// A continuous shuffling machine. There is no cut card: discards go back in after every
+    /// round, behind the `buffer` cards the machine has already queued to deal.
+    pub fn continuous(
+        deck: DeckKind,
+        num_decks: u8,
+        buffer: usize,
+        seed: Option<u64>,
+    ) -> ConfigResult<Self> {
+        if num_decks == 0 {
+            return Err(ConfigError::InvalidDecks(num_decks));
+        }
+        let total_cards = num_decks as usize * deck.size();
+        if buffer >= total_cards {
+            return Err(ConfigError::InvalidCutPosition(buffer, total_cards));
+        }
+        let mut shoe = Self::with_deck(deck, num_decks, total_cards, seed)?;
+        shoe.csm_buffer = Some(buffer);
+        Ok(shoe)
+    }
+
+    pub fn is_continuous(&self) -> bool {
+        self.csm_buffer.is_some()
+    }
+
+    /// Loads a finished round's cards back into a continuous shuffler, each at a random depth
+    /// past the buffer. A hand-shuffled shoe keeps its discards until the next shuffle.
+    pub fn return_discards(&mut self, discards: &[Card]) {
+        let Some(buffer) = self.csm_buffer else {
+            return;
+        };
+        self.cards.drain(..self.top_position.min(self.cards.len()));
+        self.top_position = 0;
+        for &card in discards {
+            let depth = self
+                .rng
+                .random_range(buffer.min(self.cards.len())..=self.cards.len());
+            self.cards.insert(depth, card);
+        }
+        self.cut_position = self.cards.len();
+    }
+
     pub fn deal(&mut self) -> GameResult<Card> {
         if self.top_position >= self.cut_position {
             return Err(GameError::ShoeNeedsReshuffling);
@@ -57,22 +110,116 @@ impl Shoe {
         Ok(card)
     }
 
+    /// Deals the next card even past the cut card, so a round in progress can finish.
+    pub fn draw(&mut self) -> GameResult<Card> {
+        let card = *self
+            .cards
+            .get(self.top_position)
+            .ok_or(GameError::ShoeEmpty)?;
+        self.top_position += 1;
+        Ok(card)
+    }
+
+    /// Cards not yet dealt, in dealing order.
+    pub fn remaining(&self) -> &[Card] {
+        &self.cards[self.top_position.min(self.cards.len())..]
+    }
+
+    /// Undealt cards measured in whole decks of the kind the shoe was built from.
+    pub fn decks_remaining(&self) -> f64 {
+        let deck_size = self.cards.len() as f64 / self.num_decks as f64;
+        self.remaining().len() as f64 / deck_size
+    }
+
+    pub fn needs_reshuffle(&self) -> bool {
+        self.top_position >= self.cut_position
+    }
+
     pub fn shuffle(&mut self) {
         self.cards.shuffle(&mut self.rng);
         self.top_position = 0
     }
+
+    /// Stacks the shoe so `cards` are dealt next (admin card injection, tests). Each is moved
+    /// up from among the undealt cards, so the shoe still holds exactly its decks.
+    pub fn inject(&mut self, cards: &[Card]) -> GameResult<()> {
+        for (offset, &card) in cards.iter().enumerate() {
+            let to = self.top_position + offset;
+            let from = self
+                .cards
+                .get(to..)
+                .and_then(|rest| rest.iter().position(|&c| c == card))
+                .ok_or_else(|| GameError::InvalidAction(format!("no {card} left in the shoe")))?;
+            let card = self.cards.remove(to + from);
+            self.cards.insert(to, card);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn remaining_shrinks_as_cards_are_dealt() {
+        let mut shoe = Shoe::new(1, 40, Some(1)).unwrap();
+        let first = shoe.remaining()[0];
+        assert_eq!(shoe.deal().unwrap(), first);
+        assert_eq!(shoe.remaining().len(), 51);
+    }
+
+    #[test]
+    fn decks_remaining_counts_down() {
+        let mut shoe = Shoe::new(2, 100, Some(1)).unwrap();
+        assert_eq!(shoe.decks_remaining(), 2.0);
+        for _ in 0..26 {
+            shoe.deal().unwrap();
+        }
+        assert_eq!(shoe.decks_remaining(), 1.5);
+    }
+
+    #[test]
+    fn continuous_shoe_takes_discards_back_behind_the_buffer() {
+        let mut shoe = Shoe::continuous(DeckKind::Standard, 1, 10, Some(4)).unwrap();
+        let dealt: Vec<Card> = (0..8).map(|_| shoe.deal().unwrap()).collect();
+        let queued = shoe.remaining()[..10].to_vec();
+
+        shoe.return_discards(&dealt);
+        assert_eq!(shoe.remaining().len(), 52);
+        assert_eq!(shoe.decks_remaining(), 1.0);
+        assert_eq!(&shoe.remaining()[..10], &queued[..]);
+        assert!(!shoe.needs_reshuffle());
+
+        assert!(Shoe::continuous(DeckKind::Standard, 1, 52, Some(4)).is_err());
+        assert!(matches!(
+            Shoe::continuous(DeckKind::Standard, 0, 10, Some(4)),
+            Err(ConfigError::InvalidDecks(0))
+        ));
+        let mut hand_shuffled = Shoe::new(1, 40, Some(4)).unwrap();
+        let card = hand_shuffled.deal().unwrap();
+        hand_shuffled.return_discards(&[card]);
+        assert_eq!(hand_shuffled.remaining().len(), 51);
+    }
+
     #[test]
     fn creation_single_deck() {
         let mut shoe = Shoe::new(1, 40, Some(42)).unwrap();
         assert!(shoe.deal().is_ok());
     }
 
+    #[test]
+    fn spanish_shoe_size() {
+        let mut shoe = Shoe::with_deck(DeckKind::Spanish, 2, 96, Some(42)).unwrap();
+        let mut dealt = 0;
+        while let Ok(card) = shoe.deal() {
+            assert_ne!(card.rank, crate::core::card::Rank::Ten);
+            dealt += 1;
+        }
+        assert_eq!(dealt, 96);
+        assert!(Shoe::with_deck(DeckKind::Spanish, 2, 97, Some(42)).is_err());
+    }
+
     #[test]
     fn creation_invalid_decks() {
         assert!(Shoe::new(0, 40, Some(42)).is_err());
@@ -115,6 +262,36 @@ mod tests {
         assert!(shoe.deal().is_ok());
     }
 
+    #[test]
+    fn draw_past_cut() {
+        let mut shoe = Shoe::new(1, 10, Some(42)).unwrap();
+        for _ in 0..10 {
+            shoe.deal().unwrap();
+        }
+        assert!(shoe.needs_reshuffle());
+        for _ in 10..52 {
+            assert!(shoe.draw().is_ok());
+        }
+        assert!(shoe.draw().is_err());
+    }
+
+    #[test]
+    fn injected_cards_dealt_first() {
+        use crate::core::card::{Rank, Suit};
+
+        let mut shoe = Shoe::new(1, 40, Some(42)).unwrap();
+        let ace = Card::new(Rank::Ace, Suit::Spades);
+        let king = Card::new(Rank::King, Suit::Hearts);
+        shoe.inject(&[ace, king]).unwrap();
+        assert_eq!(shoe.deal().unwrap(), ace);
+        assert_eq!(shoe.deal().unwrap(), king);
+        assert_eq!(shoe.remaining().len(), 50);
+        assert_eq!(shoe.remaining().iter().filter(|&&c| c == ace).count(), 0);
+
+        // one deck holds a single ace of spades
+        assert!(shoe.inject(&[ace]).is_err());
+    }
+
     #[test]
     fn deterministic_with_seed() {
         let mut shoe1 = Shoe::new(1, 52, Some(123)).unwrap();
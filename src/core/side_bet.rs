@@ -0,0 +1,472 @@
+mod paytables;
+
+use crate::core::card::Card;
+use crate::core::hand::Hand;
+use crate::core::payout::{
+    calculate_bet_the_set_payout, calculate_blackjack_spin_payout, calculate_bust_it_payout,
+    calculate_buster_blackjack_payout, calculate_hot_3_payout, calculate_kings_bounty_payout,
+    calculate_lucky_ladies_payout, calculate_lucky_lucky_payout, calculate_match_the_dealer_payout,
+    calculate_pair_squared_payout, calculate_perfect_pairs_payout, calculate_royal_match_payout,
+    calculate_super_7s_payout, calculate_top_3_payout, calculate_twenty_plus_3_payout,
+};
+use crate::core::progressive::{JackpotPrize, blazing_7s_prize, caribbean_21_prize};
+use crate::core::rules::Rules;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+pub const PERFECT_PAIRS: &str = "perfect_pairs";
+pub const TWENTY_ONE_PLUS_3: &str = "twenty_one_plus_3";
+pub const ROYAL_MATCH: &str = "royal_match";
+pub const LUCKY_LADIES: &str = "lucky_ladies";
+pub const BUST_IT: &str = "bust_it";
+pub const SUPER_7S: &str = "super_7s";
+pub const LUCKY_LUCKY: &str = "lucky_lucky";
+pub const PAIR_SQUARED: &str = "pair_squared";
+pub const BLACKJACK_SPIN: &str = "blackjack_spin";
+pub const MATCH_THE_DEALER: &str = "match_the_dealer";
+pub const BUSTER_BLACKJACK: &str = "buster_blackjack";
+pub const BET_THE_SET: &str = "bet_the_set";
+pub const TOP_3: &str = "top_3";
+pub const HOT_3: &str = "hot_3";
+pub const KINGS_BOUNTY: &str = "kings_bounty";
+pub const CARIBBEAN_21: &str = "caribbean_21";
+pub const BLAZING_7S: &str = "blazing_7s";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resolution {
+    OnDeal,      // first two cards and the dealer's upcard are enough
+    AfterDealer, // needs the player's third card or the dealer's finished hand
+}
+
+/// What a side bet gets to look at when it resolves.
+pub struct SideBetCards<'a> {
+    pub hand: &'a Hand,    // the seat's first two cards, as dealt
+    pub dealt: &'a [Card], // those two, then every card the seat received after them
+    pub dealer: &'a Hand,
+    pub upcard: Card,
+    pub spin: u32, // table randomness for wheel bets
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SideBetOutcome {
+    Returned(u64),         // stake plus winnings, 0 when the bet loses
+    Jackpot(JackpotPrize), // paid against the progressive meter kept under the bet's id
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaytableLine {
+    pub hand: String,
+    pub prize: JackpotPrize,
+}
+
+impl PaytableLine {
+    pub fn new(hand: &str, prize: JackpotPrize) -> Self {
+        Self {
+            hand: hand.to_string(),
+            prize,
+        }
+    }
+}
+
+/// A built-in bet's paytable, listing its own prizes.
+pub trait Paytable: Clone + fmt::Debug + Send + Sync + 'static {
+    fn lines(&self) -> Vec<PaytableLine>;
+}
+
+pub trait SideBet: fmt::Debug + Send + Sync {
+    fn id(&self) -> &str;
+    fn resolution(&self) -> Resolution;
+    fn evaluate(&self, bet: u32, cards: &SideBetCards) -> SideBetOutcome;
+    fn paytable(&self) -> Vec<PaytableLine>;
+
+    /// Progressive bets feed a meter under their id with every wager.
+    fn is_progressive(&self) -> bool {
+        false
+    }
+}
+
+type Evaluator<P> = fn(u32, &SideBetCards, &P) -> SideBetOutcome;
+
+/// A built-in bet: one of the paytables carried by [`Rules`] and the function that reads it.
+#[derive(Debug)]
+struct TableBet<P> {
+    id: &'static str,
+    resolution: Resolution,
+    paytable: P,
+    evaluate: Evaluator<P>,
+    progressive: bool,
+}
+
+impl<P: Paytable> SideBet for TableBet<P> {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    fn evaluate(&self, bet: u32, cards: &SideBetCards) -> SideBetOutcome {
+        (self.evaluate)(bet, cards, &self.paytable)
+    }
+
+    /// Lowest prize first.
+    fn paytable(&self) -> Vec<PaytableLine> {
+        let mut lines = self.paytable.lines();
+        lines.sort_by_key(|l| l.prize);
+        lines
+    }
+
+    fn is_progressive(&self) -> bool {
+        self.progressive
+    }
+}
+
+/// The side bets a table offers, keyed by id. Built-ins come from [`Rules`]; house bets are
+/// added with [`SideBetRegistry::register`].
+#[derive(Clone, Debug, Default)]
+pub struct SideBetRegistry {
+    bets: BTreeMap<String, Arc<dyn SideBet>>,
+}
+
+impl SideBetRegistry {
+    /// Every built-in bet that is enabled and has a paytable.
+    pub fn from_rules(rules: &Rules) -> Self {
+        let mut registry = Self::default();
+        for builtin in builtins(rules) {
+            if let Some(bet) = builtin.bet.filter(|_| builtin.enabled) {
+                registry.register(bet);
+            }
+        }
+        registry
+    }
+
+    /// Adds a bet, replacing any registered under the same id.
+    pub fn register(&mut self, bet: Arc<dyn SideBet>) {
+        self.bets.insert(bet.id().to_string(), bet);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn SideBet> {
+        self.bets.get(id).map(|b| b.as_ref())
+    }
+
+    /// The registry `rules` would build, plus every bet registered on top of this one.
+    pub fn rebuild(&self, rules: &Rules) -> Self {
+        let builtin: Vec<&str> = builtins(rules).iter().map(|b| b.id).collect();
+        let mut registry = Self::from_rules(rules);
+        for (id, bet) in &self.bets {
+            if !builtin.contains(&id.as_str()) {
+                registry.register(Arc::clone(bet));
+            }
+        }
+        registry
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn SideBet> {
+        self.bets.values().map(|b| b.as_ref())
+    }
+}
+
+/// A built-in bet as [`Rules`] sets it up.
+pub struct Builtin {
+    pub id: &'static str,
+    pub enabled: bool,
+    pub bet: Option<Arc<dyn SideBet>>, // none when the rules carry no paytable for it
+}
+
+impl Builtin {
+    fn table<P: Paytable>(
+        enabled: bool,
+        id: &'static str,
+        resolution: Resolution,
+        paytable: &Option<P>,
+        evaluate: Evaluator<P>,
+    ) -> Self {
+        Self::new(enabled, id, resolution, paytable, evaluate, false)
+    }
+
+    /// Progressive bets all settle on the deal.
+    fn progressive<P: Paytable>(
+        enabled: bool,
+        id: &'static str,
+        paytable: &Option<P>,
+        evaluate: Evaluator<P>,
+    ) -> Self {
+        Self::new(enabled, id, Resolution::OnDeal, paytable, evaluate, true)
+    }
+
+    fn new<P: Paytable>(
+        enabled: bool,
+        id: &'static str,
+        resolution: Resolution,
+        paytable: &Option<P>,
+        evaluate: Evaluator<P>,
+        progressive: bool,
+    ) -> Self {
+        let bet = paytable.as_ref().map(|paytable| {
+            Arc::new(TableBet {
+                id,
+                resolution,
+                paytable: paytable.clone(),
+                evaluate,
+                progressive,
+            }) as Arc<dyn SideBet>
+        });
+        Self { id, enabled, bet }
+    }
+}
+
+/// Every built-in bet, each read from its switch and paytable in `rules`. A new bet needs
+/// those two fields and one entry here.
+pub fn builtins(rules: &Rules) -> Vec<Builtin> {
+    use Resolution::{AfterDealer, OnDeal};
+    use SideBetOutcome::Returned;
+
+    let r = rules;
+    vec![
+        Builtin::table(
+            r.perfect_pairs_enabled,
+            PERFECT_PAIRS,
+            OnDeal,
+            &r.perfect_pairs_paytable,
+            |bet, c, p| Returned(calculate_perfect_pairs_payout(bet, c.hand, p)),
+        ),
+        Builtin::table(
+            r.twenty_one_plus_3_enabled,
+            TWENTY_ONE_PLUS_3,
+            OnDeal,
+            &r.twenty_one_plus_3_paytable,
+            |bet, c, p| Returned(calculate_twenty_plus_3_payout(bet, c.hand, c.upcard, p)),
+        ),
+        Builtin::table(
+            r.royal_match_enabled,
+            ROYAL_MATCH,
+            OnDeal,
+            &r.royal_match_paytable,
+            |bet, c, p| Returned(calculate_royal_match_payout(bet, c.hand, p)),
+        ),
+        Builtin::table(
+            r.lucky_ladies_enabled,
+            LUCKY_LADIES,
+            AfterDealer,
+            &r.lucky_ladies_paytable,
+            |bet, c, p| Returned(calculate_lucky_ladies_payout(bet, c.hand, c.dealer, p)),
+        ),
+        Builtin::table(
+            r.bust_it_enabled,
+            BUST_IT,
+            AfterDealer,
+            &r.bust_it_paytable,
+            |bet, c, p| Returned(calculate_bust_it_payout(bet, c.dealer, p)),
+        ),
+        Builtin::table(
+            r.super_7s_enabled,
+            SUPER_7S,
+            AfterDealer,
+            &r.super_7s_paytable,
+            |bet, c, p| Returned(calculate_super_7s_payout(bet, c.dealt, p)),
+        ),
+        Builtin::table(
+            r.lucky_lucky_enabled,
+            LUCKY_LUCKY,
+            OnDeal,
+            &r.lucky_lucky_paytable,
+            |bet, c, p| Returned(calculate_lucky_lucky_payout(bet, c.hand, c.upcard, p)),
+        ),
+        Builtin::table(
+            r.pair_squared_enabled,
+            PAIR_SQUARED,
+            OnDeal,
+            &r.pair_squared_paytable,
+            |bet, c, p| Returned(calculate_pair_squared_payout(bet, c.hand, p)),
+        ),
+        Builtin::table(
+            r.blackjack_spin_enabled,
+            BLACKJACK_SPIN,
+            OnDeal,
+            &r.blackjack_spin_paytable,
+            |bet, c, p| Returned(calculate_blackjack_spin_payout(bet, c.hand, c.spin, p)),
+        ),
+        Builtin::table(
+            r.match_the_dealer_enabled,
+            MATCH_THE_DEALER,
+            OnDeal,
+            &r.match_the_dealer_paytable,
+            |bet, c, p| Returned(calculate_match_the_dealer_payout(bet, c.hand, c.upcard, p)),
+        ),
+        Builtin::table(
+            r.buster_blackjack_enabled,
+            BUSTER_BLACKJACK,
+            AfterDealer,
+            &r.buster_blackjack_paytable,
+            |bet, c, p| Returned(calculate_buster_blackjack_payout(bet, c.hand, c.dealer, p)),
+        ),
+        Builtin::table(
+            r.bet_the_set_enabled,
+            BET_THE_SET,
+            OnDeal,
+            &r.bet_the_set_paytable,
+            |bet, c, p| Returned(calculate_bet_the_set_payout(bet, c.hand, c.upcard, p)),
+        ),
+        Builtin::table(
+            r.top_3_enabled,
+            TOP_3,
+            OnDeal,
+            &r.top_3_paytable,
+            |bet, c, p| Returned(calculate_top_3_payout(bet, c.hand, c.upcard, p)),
+        ),
+        Builtin::table(
+            r.hot_3_enabled,
+            HOT_3,
+            OnDeal,
+            &r.hot_3_paytable,
+            |bet, c, p| Returned(calculate_hot_3_payout(bet, c.hand, c.upcard, p)),
+        ),
+        Builtin::table(
+            r.kings_bounty_enabled,
+            KINGS_BOUNTY,
+            AfterDealer,
+            &r.kings_bounty_paytable,
+            |bet, c, p| Returned(calculate_kings_bounty_payout(bet, c.hand, c.dealer, p)),
+        ),
+        Builtin::progressive(
+            r.caribbean_21_enabled,
+            CARIBBEAN_21,
+            &r.caribbean_21_paytable,
+            |_, c, p| {
+                caribbean_21_prize(c.hand, c.upcard, p).map_or(Returned(0), SideBetOutcome::Jackpot)
+            },
+        ),
+        Builtin::progressive(
+            r.blazing_7s_enabled,
+            BLAZING_7S,
+            &r.blazing_7s_paytable,
+            |_, c, p| {
+                blazing_7s_prize(c.hand, c.upcard, p).map_or(Returned(0), SideBetOutcome::Jackpot)
+            },
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::{Rank, Suit};
+    use crate::core::payout::PerfectPairsPaytable;
+
+    #[derive(Debug)]
+    struct DealerTens;
+
+    // A house bet: pays 3 to 1 per ten-value card in the dealer's final hand.
+    impl SideBet for DealerTens {
+        fn id(&self) -> &str {
+            "dealer_tens"
+        }
+
+        fn resolution(&self) -> Resolution {
+            Resolution::AfterDealer
+        }
+
+        fn evaluate(&self, bet: u32, cards: &SideBetCards) -> SideBetOutcome {
+            let tens = cards
+                .dealer
+                .cards()
+                .iter()
+                .filter(|c| c.pip_value() == 10)
+                .count() as u64;
+            SideBetOutcome::Returned(if tens == 0 {
+                0
+            } else {
+                bet as u64 * (3 * tens + 1)
+            })
+        }
+
+        fn paytable(&self) -> Vec<PaytableLine> {
+            vec![PaytableLine {
+                hand: "per_ten".into(),
+                prize: JackpotPrize::Odds(3),
+            }]
+        }
+    }
+
+    #[test]
+    fn registry_offers_enabled_bets_with_paytables() {
+        let rules = Rules {
+            perfect_pairs_enabled: true,
+            royal_match_enabled: true,
+            royal_match_paytable: None,
+            caribbean_21_enabled: true,
+            ..Rules::new()
+        };
+        let registry = SideBetRegistry::from_rules(&rules);
+
+        let ids: Vec<&str> = registry.iter().map(|b| b.id()).collect();
+        assert_eq!(ids, vec![CARIBBEAN_21, PERFECT_PAIRS]);
+        assert!(registry.get(CARIBBEAN_21).unwrap().is_progressive());
+        assert_eq!(
+            registry.get(PERFECT_PAIRS).unwrap().resolution(),
+            Resolution::OnDeal
+        );
+    }
+
+    #[test]
+    fn house_bets_register_alongside_builtins() {
+        let mut registry = SideBetRegistry::default();
+        registry.register(Arc::new(DealerTens));
+
+        let bet = registry.get("dealer_tens").unwrap();
+        let mut dealer = Hand::new();
+        dealer.add_card(Card::new(Rank::King, Suit::Spades));
+        dealer.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        let cards = SideBetCards {
+            hand: &Hand::new(),
+            dealt: &[],
+            dealer: &dealer,
+            upcard: dealer.cards()[0],
+            spin: 0,
+        };
+        assert_eq!(bet.evaluate(5, &cards), SideBetOutcome::Returned(35));
+    }
+
+    #[test]
+    fn rebuild_keeps_house_bets() {
+        let mut rules = Rules::new();
+        rules.perfect_pairs_enabled = true;
+        let mut registry = SideBetRegistry::from_rules(&rules);
+        registry.register(Arc::new(DealerTens));
+
+        rules.perfect_pairs_enabled = false;
+        rules.top_3_enabled = true;
+        let rebuilt = registry.rebuild(&rules);
+        assert!(rebuilt.get(PERFECT_PAIRS).is_none());
+        assert!(rebuilt.get(TOP_3).is_some());
+        assert!(rebuilt.get("dealer_tens").is_some());
+    }
+
+    #[test]
+    fn paytable_lines_list_prizes_in_order() {
+        let registry = SideBetRegistry::from_rules(&Rules {
+            perfect_pairs_enabled: true,
+            perfect_pairs_paytable: Some(PerfectPairsPaytable::default()),
+            ..Rules::new()
+        });
+        let lines = registry.get(PERFECT_PAIRS).unwrap().paytable();
+        let hands: Vec<&str> = lines.iter().map(|l| l.hand.as_str()).collect();
+        assert_eq!(hands, vec!["mixed", "colored", "perfect"]);
+        assert_eq!(lines[2].prize, JackpotPrize::Odds(25));
+
+        let registry = SideBetRegistry::from_rules(&Rules {
+            blazing_7s_enabled: true,
+            blackjack_spin_enabled: true,
+            ..Rules::new()
+        });
+        let blazing = registry.get(BLAZING_7S).unwrap().paytable();
+        assert_eq!(
+            blazing.last().unwrap().prize,
+            JackpotPrize::PoolPercent(100)
+        );
+        assert_eq!(registry.get(BLACKJACK_SPIN).unwrap().paytable().len(), 12);
+    }
+}
@@ -0,0 +1,208 @@
+//! How each built-in paytable lists its prizes.
+
+use crate::core::payout::{
+    BetTheSetPaytable, BlackjackSpinPaytable, BustItPaytable, BusterBlackjackPaytable,
+    Hot3Paytable, KingsBountyPaytable, LuckyLadiesPaytable, LuckyLuckyPaytable,
+    MatchTheDealerPaytable, PairSquaredPaytable, PerfectPairsPaytable, RoyalMatchPaytable,
+    Super7sPaytable, Top3Paytable, TwentyOnePlus3Paytable,
+};
+use crate::core::progressive::{Blazing7sPaytable, Caribbean21Paytable, JackpotPrize};
+use crate::core::side_bet::{Paytable, PaytableLine};
+
+fn odds(hand: &str, odds: u32) -> PaytableLine {
+    PaytableLine::new(hand, JackpotPrize::Odds(odds))
+}
+
+impl Paytable for PerfectPairsPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("mixed", self.mixed),
+            odds("colored", self.colored),
+            odds("perfect", self.perfect),
+        ]
+    }
+}
+
+impl Paytable for TwentyOnePlus3Paytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("flush", self.flush),
+            odds("straight", self.straight),
+            odds("trips", self.trips),
+            odds("straight_flush", self.straight_flush),
+            odds("suited_trips", self.suited_trips),
+        ]
+    }
+}
+
+impl Paytable for RoyalMatchPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![odds("suited", self.suited), odds("royal", self.royal)]
+    }
+}
+
+impl Paytable for LuckyLadiesPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("any_20", self.any_20),
+            odds("suited_20", self.suited_20),
+            odds("matched_20", self.matched_20),
+            odds("queen_of_hearts_pair", self.queen_of_hearts_pair),
+            odds(
+                "queen_of_hearts_pair_vs_blackjack",
+                self.queen_of_hearts_pair_vs_blackjack,
+            ),
+        ]
+    }
+}
+
+impl Paytable for BustItPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("three_cards", self.three_cards),
+            odds("four_cards", self.four_cards),
+            odds("five_cards", self.five_cards),
+            odds("six_cards", self.six_cards),
+            odds("seven_cards", self.seven_cards),
+            odds("eight_or_more", self.eight_or_more),
+        ]
+    }
+}
+
+impl Paytable for Super7sPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("one_seven", self.one_seven),
+            odds("two_sevens", self.two_sevens),
+            odds("two_suited_sevens", self.two_suited_sevens),
+            odds("three_sevens", self.three_sevens),
+            odds("three_suited_sevens", self.three_suited_sevens),
+        ]
+    }
+}
+
+impl Paytable for LuckyLuckyPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("total_19", self.total_19),
+            odds("total_20", self.total_20),
+            odds("total_21", self.total_21),
+            odds("suited_21", self.suited_21),
+            odds("six_seven_eight", self.six_seven_eight),
+            odds("seven_seven_seven", self.seven_seven_seven),
+            odds("suited_six_seven_eight", self.suited_six_seven_eight),
+            odds("suited_seven_seven_seven", self.suited_seven_seven_seven),
+        ]
+    }
+}
+
+impl Paytable for PairSquaredPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("pair", self.pair),
+            odds("suited_pair", self.suited_pair),
+        ]
+    }
+}
+
+/// One line per wheel segment.
+impl Paytable for BlackjackSpinPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        self.wheel
+            .iter()
+            .enumerate()
+            .map(|(i, &segment)| odds(&format!("wheel_{}", i + 1), segment))
+            .collect()
+    }
+}
+
+impl Paytable for MatchTheDealerPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("unsuited_match", self.unsuited_match),
+            odds("suited_match", self.suited_match),
+        ]
+    }
+}
+
+impl Paytable for BusterBlackjackPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("three_or_four_cards", self.three_or_four_cards),
+            odds("five_cards", self.five_cards),
+            odds("six_cards", self.six_cards),
+            odds("seven_cards", self.seven_cards),
+            odds("eight_or_more", self.eight_or_more),
+            odds("blackjack_six_cards", self.blackjack_six_cards),
+            odds("blackjack_seven_cards", self.blackjack_seven_cards),
+            odds("blackjack_eight_or_more", self.blackjack_eight_or_more),
+        ]
+    }
+}
+
+impl Paytable for BetTheSetPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![odds("set", self.set), odds("suited_set", self.suited_set)]
+    }
+}
+
+impl Paytable for Top3Paytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("trips", self.trips),
+            odds("straight_flush", self.straight_flush),
+            odds("suited_trips", self.suited_trips),
+        ]
+    }
+}
+
+impl Paytable for Hot3Paytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("total_19", self.total_19),
+            odds("total_20", self.total_20),
+            odds("total_21", self.total_21),
+            odds("suited_21", self.suited_21),
+            odds("seven_seven_seven", self.seven_seven_seven),
+        ]
+    }
+}
+
+impl Paytable for KingsBountyPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            odds("any_20", self.any_20),
+            odds("suited_20", self.suited_20),
+            odds("suited_pair", self.suited_pair),
+            odds("suited_kings", self.suited_kings),
+            odds("kings_of_spades", self.kings_of_spades),
+            odds(
+                "kings_of_spades_vs_blackjack",
+                self.kings_of_spades_vs_blackjack,
+            ),
+        ]
+    }
+}
+
+impl Paytable for Caribbean21Paytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            PaytableLine::new("three_card_21", self.three_card_21),
+            PaytableLine::new("blackjack", self.blackjack),
+            PaytableLine::new("suited_blackjack", self.suited_blackjack),
+            PaytableLine::new("suited_blackjack_ace_up", self.suited_blackjack_ace_up),
+        ]
+    }
+}
+
+impl Paytable for Blazing7sPaytable {
+    fn lines(&self) -> Vec<PaytableLine> {
+        vec![
+            PaytableLine::new("one_seven", self.one_seven),
+            PaytableLine::new("two_sevens", self.two_sevens),
+            PaytableLine::new("three_sevens", self.three_sevens),
+            PaytableLine::new("three_sevens_same_color", self.three_sevens_same_color),
+            PaytableLine::new("three_suited_sevens", self.three_suited_sevens),
+        ]
+    }
+}
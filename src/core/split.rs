@@ -0,0 +1,835 @@
+use crate::core::card::{Card, Rank};
+use crate::core::hand::Hand;
+use crate::core::payout::{
+    calculate_payout, calculate_pontoon_payout, calculate_spanish_21_payout,
+    calculate_surrender_payout,
+};
+use crate::core::ranking::FIVE_CARD_TRICK;
+use crate::core::rules::{HoleCardRule, Rules, Variant};
+use crate::error::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+/// One box on the table: a hand plus the wager riding on it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitHand {
+    pub hand: Hand,
+    pub bet: u32,
+    pub free: u32, // Free Bet: house chips riding alongside `bet`, paid on a win but never lost
+    pub doubled: bool,
+    pub twisted: bool, // Pontoon: a free card has been taken, so no more can be bought
+    pub surrendered: bool,
+    pub finished: bool,
+}
+
+impl SplitHand {
+    pub fn new(bet: u32) -> Self {
+        Self {
+            hand: Hand::new(),
+            bet,
+            free: 0,
+            doubled: false,
+            twisted: false,
+            surrendered: false,
+            finished: false,
+        }
+    }
+
+    /// Everything in action on the hand, the player's chips and the house's.
+    pub fn wager(&self) -> u32 {
+        self.bet + self.free
+    }
+
+    fn is_split_aces(&self) -> bool {
+        self.hand.is_split() && self.hand.cards().first().map(|c| c.rank) == Some(Rank::Ace)
+    }
+}
+
+/// Ordered sub-hands produced by splitting, played left to right.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitHands {
+    hands: Vec<SplitHand>,
+    active: usize,
+    original_bet: u32,
+    #[serde(default)]
+    dealt: Vec<Card>, // every card the seat received, in order
+}
+
+impl SplitHands {
+    pub fn new(bet: u32) -> Self {
+        Self {
+            hands: vec![SplitHand::new(bet)],
+            active: 0,
+            original_bet: bet,
+            dealt: Vec::new(),
+        }
+    }
+
+    pub fn hands(&self) -> &[SplitHand] {
+        &self.hands
+    }
+
+    /// Deals one of the initial cards. A natural needs no decision and is finished at once.
+    pub fn deal(&mut self, card: Card) {
+        self.hands[0].hand.add_card(card);
+        self.dealt.push(card);
+        self.finish_21();
+    }
+
+    pub fn active_index(&self) -> Option<usize> {
+        (self.active < self.hands.len()).then_some(self.active)
+    }
+
+    pub fn active(&self) -> Option<&SplitHand> {
+        self.hands.get(self.active)
+    }
+
+    /// Every card the seat received in the order it came: the original two as dealt, then
+    /// each draw. Splits and switches since move cards between hands but not in here.
+    pub fn dealt_cards(&self) -> &[Card] {
+        &self.dealt
+    }
+
+    pub fn splits(&self) -> u8 {
+        (self.hands.len() - 1) as u8
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.active >= self.hands.len()
+    }
+
+    /// The player's own chips across every sub-hand; free chips are not counted.
+    pub fn total_bet(&self) -> u64 {
+        self.hands.iter().map(|h| h.bet as u64).sum()
+    }
+
+    pub fn free_chips(&self) -> u64 {
+        self.hands.iter().map(|h| h.free as u64).sum()
+    }
+
+    /// Free Bet doubles hard 9, 10 and 11 on the house.
+    pub fn free_double_offered(&self, rules: &Rules) -> bool {
+        rules.variant == Variant::FreeBet
+            && self.active().is_some_and(|a| {
+                a.hand.cards().len() == 2 && !a.hand.is_soft() && (9..=11).contains(&a.hand.value())
+            })
+    }
+
+    /// Free Bet splits every pair on the house except tens.
+    pub fn free_split_offered(&self, rules: &Rules) -> bool {
+        rules.variant == Variant::FreeBet
+            && self
+                .active()
+                .and_then(|a| a.hand.cards().first())
+                .is_some_and(|c| c.pip_value() != 10)
+    }
+
+    /// What the player puts up to double the active hand: nothing when the house covers it.
+    pub fn double_cost(&self, rules: &Rules) -> u32 {
+        match self.active() {
+            Some(_) if self.free_double_offered(rules) => 0,
+            Some(active) => active.wager(),
+            None => 0,
+        }
+    }
+
+    /// What the player puts up to split the active hand: nothing when the house covers it.
+    pub fn split_cost(&self, rules: &Rules) -> u32 {
+        match self.active() {
+            Some(_) if self.free_split_offered(rules) => 0,
+            Some(active) => active.wager(),
+            None => 0,
+        }
+    }
+
+    pub fn can_split(&self, rules: &Rules) -> bool {
+        let Some(active) = self.active() else {
+            return false;
+        };
+
+        if !active.hand.is_pair() || self.splits() >= rules.split_limit {
+            return false;
+        }
+
+        !active.is_split_aces() || rules.resplit_aces_allowed
+    }
+
+    pub fn can_hit(&self, rules: &Rules) -> bool {
+        match self.active() {
+            Some(active) => {
+                !active.doubled && (!active.is_split_aces() || rules.hit_split_aces_allowed)
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_double(&self, rules: &Rules) -> bool {
+        let Some(active) = self.active() else {
+            return false;
+        };
+
+        // Pontoon raises stakes by buying cards instead.
+        if rules.variant == Variant::Pontoon {
+            return false;
+        }
+
+        if active.hand.cards().len() != 2 || active.doubled {
+            return false;
+        }
+
+        if active.hand.is_split() && !rules.double_after_split_allowed {
+            return false;
+        }
+
+        self.can_hit(rules)
+    }
+
+    /// Surrender is only offered on the first two cards of an unsplit hand; the rule in
+    /// force decides whether that is before or after the dealer peeks. Double-down rescue
+    /// also lets a doubled hand surrender before it stands.
+    pub fn can_surrender(&self, rules: &Rules) -> bool {
+        let Some(active) = self.active() else {
+            return false;
+        };
+
+        if active.doubled {
+            return rules.double_down_rescue;
+        }
+
+        rules.surrender.allows_late() && self.hands.len() == 1 && active.hand.cards().len() == 2
+    }
+
+    pub fn surrender(&mut self, rules: &Rules) -> GameResult<()> {
+        if !self.can_surrender(rules) {
+            return Err(GameError::InvalidAction(
+                "hand cannot be surrendered".into(),
+            ));
+        }
+
+        self.hands[self.active].surrendered = true;
+        self.advance();
+        Ok(())
+    }
+
+    /// Splits the active hand in two. `first` completes the active hand and `second` the new
+    /// hand placed directly after it; the new hand carries the same wager, in house chips when
+    /// the split is free.
+    pub fn split(&mut self, rules: &Rules, first: Card, second: Card) -> GameResult<()> {
+        if !self.can_split(rules) {
+            return Err(GameError::InvalidAction("hand cannot be split".into()));
+        }
+
+        let free = self.free_split_offered(rules);
+        let active = &mut self.hands[self.active];
+        let other = active
+            .hand
+            .split_off()
+            .ok_or_else(|| GameError::InvalidAction("hand is not a pair".into()))?;
+
+        active.hand.add_card(first);
+        self.dealt.extend([first, second]);
+        let (bet, free) = if free {
+            (0, active.wager())
+        } else {
+            (active.wager(), 0)
+        };
+        let mut new_hand = SplitHand {
+            hand: other,
+            bet,
+            free,
+            doubled: false,
+            twisted: false,
+            surrendered: false,
+            finished: false,
+        };
+        new_hand.hand.add_card(second);
+        self.hands.insert(self.active + 1, new_hand);
+
+        if !rules.hit_split_aces_allowed {
+            // Split aces get one card each, unless that card makes another pair to resplit.
+            let can_resplit = rules.resplit_aces_allowed && self.splits() < rules.split_limit;
+            for hand in &mut self.hands[self.active..=self.active + 1] {
+                if hand.is_split_aces() && !(can_resplit && hand.hand.is_pair()) {
+                    hand.finished = true;
+                }
+            }
+        }
+
+        self.finish_21();
+        Ok(())
+    }
+
+    /// Blackjack Switch: trades second cards with the seat's other box before either is
+    /// played. A 21 made by the swap is finished, though only as a plain 21; a natural broken
+    /// by it plays again.
+    pub fn switch_with(&mut self, other: &mut SplitHands) -> GameResult<()> {
+        if self.hands.len() != 1 || other.hands.len() != 1 {
+            return Err(GameError::InvalidAction("split hands cannot switch".into()));
+        }
+        if !self.hands[0]
+            .hand
+            .swap_second_card(&mut other.hands[0].hand)
+        {
+            return Err(GameError::InvalidAction(
+                "only two-card hands can switch".into(),
+            ));
+        }
+
+        for hands in [self, other] {
+            hands.active = 0;
+            hands.hands[0].finished = false;
+            hands.finish_21();
+        }
+        Ok(())
+    }
+
+    pub fn hit(&mut self, rules: &Rules, card: Card) -> GameResult<()> {
+        if !self.can_hit(rules) {
+            return Err(GameError::InvalidAction(
+                "hand cannot take another card".into(),
+            ));
+        }
+
+        self.hands[self.active].twisted = true;
+        self.draw_to_active(rules, card);
+        Ok(())
+    }
+
+    /// Pontoon buys cards up to the fifth, each raising the stake by the original bet, until
+    /// the player twists a free one.
+    pub fn can_buy(&self, rules: &Rules) -> bool {
+        rules.variant == Variant::Pontoon
+            && self.can_hit(rules)
+            && self
+                .active()
+                .is_some_and(|a| !a.twisted && a.hand.cards().len() < FIVE_CARD_TRICK)
+    }
+
+    pub fn buy_cost(&self) -> u32 {
+        self.original_bet
+    }
+
+    pub fn buy(&mut self, rules: &Rules, card: Card) -> GameResult<()> {
+        if !self.can_buy(rules) {
+            return Err(GameError::InvalidAction("hand cannot buy a card".into()));
+        }
+
+        self.hands[self.active].bet += self.original_bet;
+        self.draw_to_active(rules, card);
+        Ok(())
+    }
+
+    // A hand on 21 or more is done, and so is a Pontoon hand holding a five-card trick.
+    fn draw_to_active(&mut self, rules: &Rules, card: Card) {
+        let active = &mut self.hands[self.active];
+        active.hand.add_card(card);
+        self.dealt.push(card);
+        let trick =
+            rules.variant == Variant::Pontoon && active.hand.cards().len() >= FIVE_CARD_TRICK;
+        if active.hand.value() >= 21 || trick {
+            self.advance();
+        }
+    }
+
+    /// Doubles the active hand's bet and deals it exactly one more card. With double-down
+    /// rescue the hand then waits for a stand or a surrender instead of finishing.
+    pub fn double(&mut self, rules: &Rules, card: Card) -> GameResult<()> {
+        if !self.can_double(rules) {
+            return Err(GameError::InvalidAction("hand cannot be doubled".into()));
+        }
+
+        let free = self.free_double_offered(rules);
+        let active = &mut self.hands[self.active];
+        if free {
+            active.free += active.wager();
+        } else {
+            active.bet += active.wager();
+        }
+        active.doubled = true;
+        active.hand.add_card(card);
+        self.dealt.push(card);
+        if !rules.double_down_rescue || active.hand.value() >= 21 {
+            self.advance();
+        }
+        Ok(())
+    }
+
+    pub fn stand(&mut self) -> GameResult<()> {
+        if self.is_finished() {
+            return Err(GameError::InvalidAction("no hand left to play".into()));
+        }
+
+        self.advance();
+        Ok(())
+    }
+
+    /// Total returned to the player across every sub-hand, stakes included. Free chips earn
+    /// their winnings but go back to the house.
+    pub fn settle(&self, dealer_hand: &Hand, rules: &Rules) -> u64 {
+        let returned = self
+            .hands
+            .iter()
+            .map(|h| {
+                let wager = h.wager();
+                let returned = if h.surrendered {
+                    calculate_surrender_payout(wager, dealer_hand, rules)
+                } else if rules.variant == Variant::Spanish21 {
+                    calculate_spanish_21_payout(wager, &h.hand, dealer_hand, h.doubled, rules)
+                } else if rules.variant == Variant::Pontoon {
+                    calculate_pontoon_payout(wager, &h.hand, dealer_hand, rules)
+                } else {
+                    calculate_payout(wager, &h.hand, dealer_hand, rules)
+                };
+                returned.saturating_sub(h.free as u64)
+            })
+            .sum();
+
+        if dealer_hand.is_blackjack() && rules.hole_card == HoleCardRule::Obo {
+            // Only the original wager is lost; double and split money comes back.
+            return u64::max(returned, self.total_bet() - self.original_bet as u64);
+        }
+
+        returned
+    }
+
+    fn advance(&mut self) {
+        if let Some(active) = self.hands.get_mut(self.active) {
+            active.finished = true;
+        }
+        self.active += 1;
+        self.finish_21();
+    }
+
+    // Skips over hands that need no decision: already finished or sitting on 21.
+    fn finish_21(&mut self) {
+        while let Some(active) = self.hands.get_mut(self.active) {
+            if !active.finished && active.hand.value() < 21 {
+                break;
+            }
+            active.finished = true;
+            self.active += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::Suit;
+    use crate::core::rules::SurrenderRule;
+
+    fn card(rank: Rank) -> Card {
+        Card::new(rank, Suit::Spades)
+    }
+
+    fn dealt(first: Rank, second: Rank, bet: u32) -> SplitHands {
+        let mut hands = SplitHands::new(bet);
+        hands.deal(card(first));
+        hands.deal(card(second));
+        hands
+    }
+
+    fn make_rules() -> Rules {
+        Rules {
+            split_limit: 3,
+            ..Rules::new()
+        }
+    }
+
+    #[test]
+    fn split_creates_ordered_hands() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+
+        hands
+            .split(&rules, card(Rank::Three), card(Rank::Ten))
+            .unwrap();
+        assert_eq!(hands.hands().len(), 2);
+        assert_eq!(hands.active_index(), Some(0));
+        assert_eq!(hands.hands()[0].hand.value(), 11);
+        assert_eq!(hands.hands()[1].hand.value(), 18);
+        assert_eq!(hands.total_bet(), 20);
+    }
+
+    #[test]
+    fn switch_trades_second_cards() {
+        let mut left = dealt(Rank::Ten, Rank::Six, 10);
+        let mut right = dealt(Rank::Five, Rank::Ace, 10);
+
+        left.switch_with(&mut right).unwrap();
+        assert!(left.is_finished());
+        assert_eq!(left.hands()[0].hand.value(), 21);
+        assert_eq!(right.active_index(), Some(0));
+        assert_eq!(right.hands()[0].hand.value(), 11);
+
+        // Switching back breaks the 21 and puts the box back in play.
+        left.switch_with(&mut right).unwrap();
+        assert_eq!(left.active_index(), Some(0));
+    }
+
+    #[test]
+    fn switch_needs_unsplit_hands() {
+        let rules = make_rules();
+        let mut left = dealt(Rank::Eight, Rank::Eight, 10);
+        let mut right = dealt(Rank::Five, Rank::Six, 10);
+        left.split(&rules, card(Rank::Two), card(Rank::Three))
+            .unwrap();
+        assert!(left.switch_with(&mut right).is_err());
+    }
+
+    #[test]
+    fn dealt_cards_survive_split() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Seven, Rank::Seven, 10);
+        hands
+            .split(&rules, card(Rank::Four), card(Rank::Ten))
+            .unwrap();
+
+        let ranks: Vec<Rank> = hands.dealt_cards().iter().map(|c| c.rank).collect();
+        assert_eq!(ranks, [Rank::Seven, Rank::Seven, Rank::Four, Rank::Ten]);
+
+        // A resplit leaves the drawn seven in the second hand, not the dealt one.
+        let mut hands = dealt(Rank::Seven, Rank::Seven, 10);
+        hands
+            .split(&rules, card(Rank::Seven), card(Rank::Ten))
+            .unwrap();
+        hands
+            .split(&rules, card(Rank::Four), card(Rank::Two))
+            .unwrap();
+        let ranks: Vec<Rank> = hands.dealt_cards().iter().map(|c| c.rank).collect();
+        assert_eq!(ranks[..3], [Rank::Seven; 3]);
+        assert_eq!(hands.hands()[0].hand.cards().len(), 2);
+
+        let mut left = dealt(Rank::Ten, Rank::Six, 10);
+        let mut right = dealt(Rank::Five, Rank::Ace, 10);
+        left.switch_with(&mut right).unwrap();
+        let ranks: Vec<Rank> = left.dealt_cards().iter().map(|c| c.rank).collect();
+        assert_eq!(ranks, [Rank::Ten, Rank::Six]);
+    }
+
+    #[test]
+    fn split_limit_enforced() {
+        let mut rules = make_rules();
+        rules.split_limit = 1;
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+
+        hands
+            .split(&rules, card(Rank::Eight), card(Rank::Two))
+            .unwrap();
+        assert!(!hands.can_split(&rules));
+        assert!(
+            hands
+                .split(&rules, card(Rank::Two), card(Rank::Two))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn resplit_up_to_limit() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+
+        hands
+            .split(&rules, card(Rank::Eight), card(Rank::Two))
+            .unwrap();
+        hands
+            .split(&rules, card(Rank::Eight), card(Rank::Three))
+            .unwrap();
+        hands
+            .split(&rules, card(Rank::Four), card(Rank::Five))
+            .unwrap();
+        assert_eq!(hands.splits(), 3);
+        assert!(!hands.can_split(&rules));
+        assert_eq!(hands.total_bet(), 40);
+    }
+
+    #[test]
+    fn free_bet_splits_and_doubles_on_the_house() {
+        let rules = Rules::free_bet();
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+        assert_eq!(hands.split_cost(&rules), 0);
+        hands
+            .split(&rules, card(Rank::Two), card(Rank::Three))
+            .unwrap();
+        assert_eq!(hands.hands()[1].bet, 0);
+        assert_eq!(hands.hands()[1].free, 10);
+
+        // 8-2 doubles free, the free-split 8-3 doubles free too
+        assert_eq!(hands.double_cost(&rules), 0);
+        hands.double(&rules, card(Rank::Nine)).unwrap();
+        hands.double(&rules, card(Rank::Six)).unwrap();
+        assert_eq!(hands.total_bet(), 10);
+        assert_eq!(hands.free_chips(), 30);
+
+        // dealer 18: the 19 wins 20 on 10 own chips, the 17 loses nothing of the player's
+        let mut dealer = Hand::new();
+        dealer.add_card(card(Rank::Ten));
+        dealer.add_card(card(Rank::Eight));
+        assert_eq!(hands.settle(&dealer, &rules), 30);
+    }
+
+    #[test]
+    fn free_bet_charges_tens_and_soft_doubles() {
+        let rules = Rules::free_bet();
+        let tens = dealt(Rank::King, Rank::Queen, 10);
+        assert_eq!(tens.split_cost(&rules), 10);
+        let soft = dealt(Rank::Ace, Rank::Nine, 10);
+        assert_eq!(soft.double_cost(&rules), 10);
+        let hard = dealt(Rank::Six, Rank::Four, 10);
+        assert_eq!(hard.double_cost(&rules), 0);
+        assert_eq!(hard.double_cost(&make_rules()), 10);
+    }
+
+    #[test]
+    fn pontoon_buys_until_twist() {
+        let rules = Rules::pontoon();
+        let mut hands = dealt(Rank::Two, Rank::Three, 10);
+        assert!(!hands.can_double(&rules));
+        assert!(!hands.can_buy(&make_rules()));
+
+        hands.buy(&rules, card(Rank::Two)).unwrap();
+        assert_eq!(hands.total_bet(), 20);
+        hands.hit(&rules, card(Rank::Three)).unwrap();
+        assert!(!hands.can_buy(&rules));
+        assert!(hands.buy(&rules, card(Rank::Two)).is_err());
+
+        // the fifth card makes a trick and ends the hand
+        hands.hit(&rules, card(Rank::Four)).unwrap();
+        assert!(hands.is_finished());
+    }
+
+    #[test]
+    fn non_pair_cannot_split() {
+        let rules = make_rules();
+        let hands = dealt(Rank::Eight, Rank::Nine, 10);
+        assert!(!hands.can_split(&rules));
+    }
+
+    #[test]
+    fn split_aces_get_one_card() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Ace, Rank::Ace, 10);
+
+        hands
+            .split(&rules, card(Rank::Five), card(Rank::Six))
+            .unwrap();
+        assert!(hands.is_finished());
+        assert!(hands.hands().iter().all(|h| h.finished));
+    }
+
+    #[test]
+    fn hit_split_aces_allowed() {
+        let mut rules = make_rules();
+        rules.hit_split_aces_allowed = true;
+        let mut hands = dealt(Rank::Ace, Rank::Ace, 10);
+
+        hands
+            .split(&rules, card(Rank::Five), card(Rank::Six))
+            .unwrap();
+        assert!(hands.can_hit(&rules));
+        hands.hit(&rules, card(Rank::Two)).unwrap();
+        assert_eq!(hands.hands()[0].hand.value(), 18);
+    }
+
+    #[test]
+    fn resplit_aces_rule() {
+        let mut rules = make_rules();
+        let mut hands = dealt(Rank::Ace, Rank::Ace, 10);
+        hands
+            .split(&rules, card(Rank::Ace), card(Rank::Six))
+            .unwrap();
+        assert!(!hands.can_split(&rules));
+        assert!(hands.is_finished());
+
+        rules.resplit_aces_allowed = true;
+        let mut hands = dealt(Rank::Ace, Rank::Ace, 10);
+        hands
+            .split(&rules, card(Rank::Ace), card(Rank::Six))
+            .unwrap();
+        assert!(hands.can_split(&rules));
+        hands
+            .split(&rules, card(Rank::Nine), card(Rank::Seven))
+            .unwrap();
+        assert_eq!(hands.hands().len(), 3);
+        assert!(hands.is_finished());
+    }
+
+    #[test]
+    fn double_after_split_rule() {
+        let mut rules = make_rules();
+        let mut hands = dealt(Rank::Five, Rank::Five, 10);
+        hands
+            .split(&rules, card(Rank::Six), card(Rank::Two))
+            .unwrap();
+        assert!(hands.can_double(&rules));
+
+        rules.double_after_split_allowed = false;
+        assert!(!hands.can_double(&rules));
+        assert!(hands.double(&rules, card(Rank::Ten)).is_err());
+    }
+
+    #[test]
+    fn play_through_each_hand() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Nine, Rank::Nine, 10);
+        hands
+            .split(&rules, card(Rank::Two), card(Rank::Ten))
+            .unwrap();
+
+        hands.double(&rules, card(Rank::Nine)).unwrap();
+        assert_eq!(hands.active_index(), Some(1));
+        hands.stand().unwrap();
+        assert!(hands.is_finished());
+        assert!(hands.stand().is_err());
+        assert_eq!(hands.total_bet(), 30);
+    }
+
+    #[test]
+    fn hit_to_21_moves_on() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Seven, Rank::Seven, 10);
+        hands
+            .split(&rules, card(Rank::Four), card(Rank::Two))
+            .unwrap();
+
+        hands.hit(&rules, card(Rank::King)).unwrap();
+        assert_eq!(hands.active_index(), Some(1));
+    }
+
+    #[test]
+    fn settle_each_hand_independently() {
+        let rules = make_rules();
+        let mut hands = dealt(Rank::Ace, Rank::Ace, 10);
+        hands
+            .split(&rules, card(Rank::King), card(Rank::Five))
+            .unwrap();
+
+        let mut dealer = Hand::new();
+        dealer.add_card(card(Rank::Ten));
+        dealer.add_card(card(Rank::Eight));
+
+        // A + K after a split pays even money, A + 5 (soft 16) loses.
+        assert_eq!(hands.settle(&dealer, &rules), 20);
+    }
+
+    #[test]
+    fn surrender_first_two_cards_only() {
+        let mut rules = make_rules();
+        let mut hands = dealt(Rank::Ten, Rank::Six, 10);
+        assert!(!hands.can_surrender(&rules));
+
+        rules.surrender = SurrenderRule::Late;
+        assert!(hands.can_surrender(&rules));
+        hands.hit(&rules, card(Rank::Two)).unwrap();
+        assert!(!hands.can_surrender(&rules));
+    }
+
+    #[test]
+    fn no_surrender_after_split() {
+        let rules = Rules {
+            surrender: SurrenderRule::Late,
+            ..make_rules()
+        };
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+        hands
+            .split(&rules, card(Rank::Ten), card(Rank::Nine))
+            .unwrap();
+        assert!(!hands.can_surrender(&rules));
+        assert!(hands.surrender(&rules).is_err());
+    }
+
+    #[test]
+    fn surrender_settles_half() {
+        let rules = Rules {
+            surrender: SurrenderRule::Late,
+            ..make_rules()
+        };
+        let mut hands = dealt(Rank::Ten, Rank::Six, 10);
+        hands.surrender(&rules).unwrap();
+        assert!(hands.is_finished());
+
+        let mut dealer = Hand::new();
+        dealer.add_card(card(Rank::Ten));
+        dealer.add_card(card(Rank::Eight));
+        assert_eq!(hands.settle(&dealer, &rules), 5);
+    }
+
+    #[test]
+    fn double_down_rescue() {
+        let rules = Rules {
+            double_down_rescue: true,
+            ..make_rules()
+        };
+        let mut hands = dealt(Rank::Six, Rank::Five, 10);
+        hands.double(&rules, card(Rank::Two)).unwrap();
+
+        // still waiting on the player, but no more cards
+        assert!(!hands.is_finished());
+        assert!(!hands.can_hit(&rules));
+        assert!(!hands.can_double(&rules));
+        hands.surrender(&rules).unwrap();
+        assert!(hands.is_finished());
+
+        let mut dealer = Hand::new();
+        dealer.add_card(card(Rank::Ten));
+        dealer.add_card(card(Rank::Eight));
+        // the doubled 20 comes back less the original 10
+        assert_eq!(hands.settle(&dealer, &rules), 10);
+    }
+
+    #[test]
+    fn no_rescue_without_rule() {
+        let rules = Rules {
+            surrender: SurrenderRule::Late,
+            ..make_rules()
+        };
+        let mut hands = dealt(Rank::Six, Rank::Five, 10);
+        hands.double(&rules, card(Rank::Two)).unwrap();
+        assert!(hands.is_finished());
+        assert!(hands.surrender(&rules).is_err());
+    }
+
+    fn dealer_blackjack() -> Hand {
+        let mut dealer = Hand::new();
+        dealer.add_card(card(Rank::Ace));
+        dealer.add_card(card(Rank::King));
+        dealer
+    }
+
+    #[test]
+    fn enhc_loses_splits_and_doubles() {
+        let mut rules = make_rules();
+        rules.hole_card = HoleCardRule::Enhc;
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+        hands
+            .split(&rules, card(Rank::Three), card(Rank::Ten))
+            .unwrap();
+        hands.double(&rules, card(Rank::Nine)).unwrap();
+        hands.stand().unwrap();
+
+        assert_eq!(hands.settle(&dealer_blackjack(), &rules), 0);
+    }
+
+    #[test]
+    fn obo_refunds_splits_and_doubles() {
+        let mut rules = make_rules();
+        rules.hole_card = HoleCardRule::Obo;
+        let mut hands = dealt(Rank::Eight, Rank::Eight, 10);
+        hands
+            .split(&rules, card(Rank::Three), card(Rank::Ten))
+            .unwrap();
+        hands.double(&rules, card(Rank::Nine)).unwrap();
+        hands.stand().unwrap();
+
+        // 30 wagered, only the original 10 is lost.
+        assert_eq!(hands.settle(&dealer_blackjack(), &rules), 20);
+    }
+
+    #[test]
+    fn obo_natural_pushes_dealer_natural() {
+        let mut rules = make_rules();
+        rules.hole_card = HoleCardRule::Obo;
+        let hands = dealt(Rank::Ace, Rank::Queen, 10);
+
+        assert_eq!(hands.settle(&dealer_blackjack(), &rules), 10);
+    }
+}
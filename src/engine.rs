@@ -1,4 +1,4 @@
 mod admin;
-mod bot;
-mod game;
-mod state;
+pub mod bot;
+pub mod game;
+pub mod state;
@@ -1 +1,1256 @@
+//! Computer players. A [`BotStrategy`] decides bets and plays for one seat from a [`BotView`] of
+//! what a player at the table could see; [`Bots`] drives the bot seats of a [`Game`] through the
+//! same `apply`/`advance` calls a human's input goes through.
 
+pub mod betting;
+pub mod personality;
+
+use crate::core::card::Card;
+use crate::core::payout::RoundingPolicy;
+use crate::core::rules::Rules;
+use crate::core::split::SplitHands;
+use crate::engine::bot::betting::BettingPlan;
+use crate::engine::bot::personality::TableEvent;
+use crate::engine::game::Game;
+use crate::error::{ConfigError, ConfigResult, GameError, GameResult};
+use crate::strategy::chart::{Play, StrategyChart};
+use crate::strategy::count::{CountSystem, Counter};
+use crate::types::action::{Action, PlayerAction};
+use crate::types::phase::Phase;
+use crate::types::player::{Player, PlayerHand};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The table as one seat sees it.
+#[derive(Clone, Debug)]
+pub struct BotView<'a> {
+    pub rules: &'a Rules,
+    pub hands: &'a [PlayerHand],  // every seat's, not only the bot's own
+    pub dealer_cards: &'a [Card], // face-up cards only
+    pub decks_remaining: f64,
+    pub shuffle_pending: bool, // the cut card is out, or a shuffler takes the discards back
+    pub credits: u32,
+    pub spectating: bool,
+}
+
+impl<'a> BotView<'a> {
+    pub fn new(game: &'a Game, player_id: Uuid) -> Self {
+        Self {
+            rules: game.rules(),
+            hands: game.hands(),
+            dealer_cards: game.visible_dealer_cards(),
+            decks_remaining: game.shoe().decks_remaining(),
+            shuffle_pending: game.shoe().needs_reshuffle() || game.shoe().is_continuous(),
+            credits: game.player(player_id).map_or(0, |p| p.credits),
+            spectating: game.player(player_id).is_some_and(|p| p.is_spectator),
+        }
+    }
+
+    pub fn upcard(&self) -> Option<Card> {
+        self.dealer_cards.first().copied()
+    }
+
+    /// Every card face up on the table.
+    pub fn seen_cards(&self) -> Vec<Card> {
+        let mut cards: Vec<Card> = self
+            .hands
+            .iter()
+            .flat_map(|h| h.hands.dealt_cards().iter().copied())
+            .collect();
+        cards.extend_from_slice(self.dealer_cards);
+        cards
+    }
+}
+
+/// What a bot does with its seat before a round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SeatChoice {
+    Play,
+    Watch, // sit the round out as a spectator
+    Leave,
+}
+
+/// How a bot seat bets and plays.
+pub trait BotStrategy: Send {
+    fn name(&self) -> &'static str;
+
+    /// Asked before every round, ahead of [`BotStrategy::bet`].
+    fn seat_choice(&mut self, _view: &BotView) -> SeatChoice {
+        SeatChoice::Play
+    }
+
+    /// The wager for the next round before table limits are applied. Zero sits the round out.
+    fn bet(&mut self, view: &BotView) -> u32;
+
+    /// The play for the seat's active hand.
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action;
+
+    fn insure(&mut self, _view: &BotView) -> bool {
+        false
+    }
+
+    /// How long to wait before each play when [`Bots::update`] paces the table.
+    fn think_time(&mut self, _view: &BotView) -> Duration {
+        Duration::ZERO
+    }
+
+    /// A chat line about what just happened, if the bot has anything to say.
+    fn react(&mut self, _events: &[TableEvent]) -> Option<String> {
+        None
+    }
+
+    /// Called once the round is settled, with every card on the table face up and the seat's
+    /// net result.
+    fn round_over(&mut self, _view: &BotView, _net: i64) {}
+}
+
+/// Every play the active hand can make with `credits` left to put up.
+pub fn legal_actions(hands: &SplitHands, rules: &Rules, credits: u32) -> Vec<Action> {
+    let mut legal = vec![Action::Stand];
+    if hands.can_hit(rules) {
+        legal.push(Action::Hit);
+    }
+    if affords_double(hands, rules, credits) {
+        legal.push(Action::Double);
+    }
+    if affords_split(hands, rules, credits) {
+        legal.push(Action::Split);
+    }
+    if hands.can_buy(rules) && hands.buy_cost() <= credits {
+        legal.push(Action::Buy);
+    }
+    if hands.can_surrender(rules) {
+        legal.push(Action::Surrender);
+    }
+    legal
+}
+
+fn affords_double(hands: &SplitHands, rules: &Rules, credits: u32) -> bool {
+    hands.can_double(rules) && hands.double_cost(rules) <= credits
+}
+
+fn affords_split(hands: &SplitHands, rules: &Rules, credits: u32) -> bool {
+    hands.can_split(rules) && hands.split_cost(rules) <= credits
+}
+
+/// The action a chart play comes to for the active hand. A split or surrender the hand cannot
+/// make, or `credits` cannot cover, is played on its total, and a double falls back the way
+/// the chart code says.
+pub fn play_action(
+    play: Option<Play>,
+    chart: &StrategyChart,
+    hands: &SplitHands,
+    upcard: Card,
+    rules: &Rules,
+    credits: u32,
+) -> Action {
+    let Some(active) = hands.active() else {
+        return Action::Stand;
+    };
+    let hand = &active.hand;
+    let play = match play {
+        Some(Play::Surrender) if !hands.can_surrender(rules) => chart.lookup_total(hand, upcard),
+        Some(Play::Split) if !affords_split(hands, rules, credits) => {
+            chart.lookup_total(hand, upcard)
+        }
+        play => play,
+    };
+    match play.unwrap_or(Play::Stand) {
+        Play::Surrender => Action::Surrender,
+        Play::Split => Action::Split,
+        Play::DoubleOrHit | Play::DoubleOrStand if affords_double(hands, rules, credits) => {
+            Action::Double
+        }
+        Play::Hit | Play::DoubleOrHit if hands.can_hit(rules) => Action::Hit,
+        _ => Action::Stand,
+    }
+}
+
+/// The chart's play for the active hand. With no upcard to read (a Pontoon banker shows
+/// none) the hand is drawn to 17 like the dealer's.
+pub fn chart_action(
+    chart: &StrategyChart,
+    hands: &SplitHands,
+    upcard: Option<Card>,
+    rules: &Rules,
+    credits: u32,
+) -> Action {
+    let Some(active) = hands.active() else {
+        return Action::Stand;
+    };
+    match upcard {
+        Some(upcard) => play_action(
+            chart.lookup(&active.hand, upcard),
+            chart,
+            hands,
+            upcard,
+            rules,
+            credits,
+        ),
+        None => hit_below(17, hands, rules),
+    }
+}
+
+fn hit_below(total: u8, hands: &SplitHands, rules: &Rules) -> Action {
+    match hands.active() {
+        Some(active) if active.hand.value() < total && hands.can_hit(rules) => Action::Hit,
+        _ => Action::Stand,
+    }
+}
+
+/// Flat minimum bets and any legal play, picked at random.
+pub struct RandomBot {
+    rng: ChaCha8Rng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl BotStrategy for RandomBot {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        view.rules.min_bet
+    }
+
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        let mut legal = legal_actions(hands, view.rules, view.credits);
+        legal.swap_remove(self.rng.random_range(0..legal.len()))
+    }
+
+    fn insure(&mut self, _view: &BotView) -> bool {
+        self.rng.random_bool(0.5)
+    }
+}
+
+/// Flat minimum bets, hitting below 17 and never doubling, splitting or surrendering.
+pub struct MimicDealerBot;
+
+impl BotStrategy for MimicDealerBot {
+    fn name(&self) -> &'static str {
+        "mimic-dealer"
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        view.rules.min_bet
+    }
+
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        hit_below(17, hands, view.rules)
+    }
+}
+
+/// Flat minimum bets, standing on any hard 12 or more and drawing soft hands to 18.
+pub struct NeverBustBot;
+
+impl BotStrategy for NeverBustBot {
+    fn name(&self) -> &'static str {
+        "never-bust"
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        view.rules.min_bet
+    }
+
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        match hands.active() {
+            Some(active) if active.hand.is_soft() => hit_below(18, hands, view.rules),
+            _ => hit_below(12, hands, view.rules),
+        }
+    }
+}
+
+/// Flat minimum bets played off the chart generated for the table's rules.
+pub struct BasicBot {
+    chart: Arc<StrategyChart>,
+}
+
+impl BasicBot {
+    pub fn new(chart: Arc<StrategyChart>) -> Self {
+        Self { chart }
+    }
+}
+
+impl BotStrategy for BasicBot {
+    fn name(&self) -> &'static str {
+        "basic"
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        view.rules.min_bet
+    }
+
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        chart_action(&self.chart, hands, view.upcard(), view.rules, view.credits)
+    }
+}
+
+/// The hand a count deviation applies to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeviationHand {
+    Hard(u8),
+    Pair(u8), // by pip value
+}
+
+/// A departure from the chart at a Hi-Lo true count: at or above `index` the hand is played
+/// `play`, below it `otherwise`, or off the chart if that is `None`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Deviation {
+    pub hand: DeviationHand,
+    pub upcard: u8, // pip value, 1 for an Ace
+    pub index: i8,
+    pub play: Play,
+    pub otherwise: Option<Play>,
+}
+
+const fn deviation(
+    hand: DeviationHand,
+    upcard: u8,
+    index: i8,
+    play: Play,
+    otherwise: Play,
+) -> Deviation {
+    Deviation {
+        hand,
+        upcard,
+        index,
+        play,
+        otherwise: Some(otherwise),
+    }
+}
+
+const fn surrender(total: u8, upcard: u8, index: i8) -> Deviation {
+    Deviation {
+        hand: DeviationHand::Hard(total),
+        upcard,
+        index,
+        play: Play::Surrender,
+        otherwise: None,
+    }
+}
+
+/// Insurance is worth taking from this Hi-Lo true count up.
+pub const INSURANCE_INDEX: f64 = 3.0;
+
+/// Don Schlesinger's Illustrious 18 for multi-deck S17, less insurance (see
+/// [`INSURANCE_INDEX`]), in order of value.
+pub const ILLUSTRIOUS_18: [Deviation; 17] = {
+    use DeviationHand::{Hard, Pair};
+    use Play::{DoubleOrHit, Hit, Split, Stand};
+    [
+        deviation(Hard(16), 10, 0, Stand, Hit),
+        deviation(Hard(15), 10, 4, Stand, Hit),
+        deviation(Pair(10), 5, 5, Split, Stand),
+        deviation(Pair(10), 6, 4, Split, Stand),
+        deviation(Hard(10), 10, 4, DoubleOrHit, Hit),
+        deviation(Hard(12), 3, 2, Stand, Hit),
+        deviation(Hard(12), 2, 3, Stand, Hit),
+        deviation(Hard(11), 1, 1, DoubleOrHit, Hit),
+        deviation(Hard(9), 2, 1, DoubleOrHit, Hit),
+        deviation(Hard(10), 1, 4, DoubleOrHit, Hit),
+        deviation(Hard(9), 7, 3, DoubleOrHit, Hit),
+        deviation(Hard(16), 9, 5, Stand, Hit),
+        deviation(Hard(13), 2, -1, Stand, Hit),
+        deviation(Hard(12), 4, 0, Stand, Hit),
+        deviation(Hard(12), 5, -2, Stand, Hit),
+        deviation(Hard(12), 6, -1, Stand, Hit),
+        deviation(Hard(13), 3, -2, Stand, Hit),
+    ]
+};
+
+/// The Fab 4 late surrender deviations.
+pub const FAB_4: [Deviation; 4] = [
+    surrender(14, 10, 3),
+    surrender(15, 10, 0),
+    surrender(15, 9, 2),
+    surrender(15, 1, 1),
+];
+
+impl Deviation {
+    /// The play at `true_count`, or `None` to leave it to the chart.
+    pub fn at(&self, true_count: f64) -> Option<Play> {
+        if true_count >= self.index as f64 {
+            Some(self.play)
+        } else {
+            self.otherwise
+        }
+    }
+
+    fn matches(&self, hands: &SplitHands, upcard: Card, rules: &Rules) -> bool {
+        let Some(active) = hands.active() else {
+            return false;
+        };
+        let hand = &active.hand;
+        let splittable = hand.is_pair() && hands.can_split(rules);
+        let matched = match self.hand {
+            DeviationHand::Pair(value) => splittable && hand.cards()[0].pip_value() == value,
+            DeviationHand::Hard(total) => !splittable && !hand.is_soft() && hand.value() == total,
+        };
+        matched && upcard.pip_value() == self.upcard
+    }
+}
+
+/// A Hi-Lo counter playing the Illustrious 18 and Fab 4 over basic strategy, betting to its
+/// [`BettingPlan`].
+pub struct IndexBot {
+    chart: Arc<StrategyChart>,
+    counter: Counter,
+    plan: BettingPlan,
+}
+
+impl IndexBot {
+    /// Bets the default one-to-eight spread.
+    pub fn new(chart: Arc<StrategyChart>, rules: &Rules) -> Self {
+        Self::with_plan(chart, rules, BettingPlan::default())
+    }
+
+    pub fn with_plan(chart: Arc<StrategyChart>, rules: &Rules, plan: BettingPlan) -> Self {
+        Self {
+            chart,
+            counter: Counter::new(CountSystem::HiLo, rules.num_decks),
+            plan,
+        }
+    }
+
+    /// The true count including the cards of the round in progress.
+    pub fn true_count(&self, view: &BotView) -> f64 {
+        let mut counter = self.counter.clone();
+        counter.see_all(&view.seen_cards());
+        counter.true_count_with(view.decks_remaining)
+    }
+}
+
+impl BotStrategy for IndexBot {
+    fn name(&self) -> &'static str {
+        "hi-lo-index"
+    }
+
+    fn seat_choice(&mut self, view: &BotView) -> SeatChoice {
+        if view.shuffle_pending {
+            self.counter.reset();
+        }
+        self.plan
+            .seat_choice(self.true_count(view), view.credits, view.spectating)
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        self.plan
+            .wager(self.true_count(view), view.credits, view.rules)
+    }
+
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        let Some(upcard) = view.upcard() else {
+            return chart_action(&self.chart, hands, None, view.rules, view.credits);
+        };
+        let rules = view.rules;
+        let true_count = self.true_count(view);
+        let deviation = |table: &[Deviation]| {
+            table
+                .iter()
+                .find(|d| d.matches(hands, upcard, rules))
+                .and_then(|d| d.at(true_count))
+        };
+
+        let chart_play = hands
+            .active()
+            .and_then(|a| self.chart.lookup(&a.hand, upcard));
+        let play = if hands.can_surrender(rules) {
+            deviation(&FAB_4)
+                .or(chart_play.filter(|&p| p == Play::Surrender))
+                .or_else(|| deviation(&ILLUSTRIOUS_18))
+        } else {
+            deviation(&ILLUSTRIOUS_18)
+        };
+        play_action(
+            play.or(chart_play),
+            &self.chart,
+            hands,
+            upcard,
+            rules,
+            view.credits,
+        )
+    }
+
+    fn insure(&mut self, view: &BotView) -> bool {
+        self.true_count(view) >= INSURANCE_INDEX
+    }
+
+    fn round_over(&mut self, view: &BotView, _net: i64) {
+        self.counter.see_all(&view.seen_cards());
+    }
+}
+
+/// Betting progressions, each played over basic strategy.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Progression {
+    Martingale,     // double after every loss, back to one unit after a win
+    Paroli,         // double after a win, for up to three wins in a row
+    OneThreeTwoSix, // 1, 3, 2 then 6 units through a run of wins
+}
+
+pub struct ProgressionBot {
+    progression: Progression,
+    step: u32,
+    chart: Arc<StrategyChart>,
+}
+
+impl ProgressionBot {
+    // Martingale doubling stops here; the table maximum usually cuts in well before.
+    const MAX_DOUBLINGS: u32 = 16;
+
+    pub fn new(progression: Progression, chart: Arc<StrategyChart>) -> Self {
+        Self {
+            progression,
+            step: 0,
+            chart,
+        }
+    }
+
+    pub fn units(&self) -> u32 {
+        match self.progression {
+            Progression::Martingale | Progression::Paroli => 1 << self.step,
+            Progression::OneThreeTwoSix => [1, 3, 2, 6][self.step as usize],
+        }
+    }
+
+    fn record(&mut self, net: i64) {
+        // A push leaves the progression where it was.
+        self.step = match (self.progression, net.signum()) {
+            (_, 0) => self.step,
+            (Progression::Martingale, -1) => (self.step + 1).min(Self::MAX_DOUBLINGS),
+            (Progression::Martingale, _) => 0,
+            (Progression::Paroli, 1) => (self.step + 1) % 3,
+            (Progression::OneThreeTwoSix, 1) => (self.step + 1) % 4,
+            _ => 0,
+        };
+    }
+}
+
+impl BotStrategy for ProgressionBot {
+    fn name(&self) -> &'static str {
+        match self.progression {
+            Progression::Martingale => "martingale",
+            Progression::Paroli => "paroli",
+            Progression::OneThreeTwoSix => "1-3-2-6",
+        }
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        view.rules.min_bet.saturating_mul(self.units())
+    }
+
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        chart_action(&self.chart, hands, view.upcard(), view.rules, view.credits)
+    }
+
+    fn round_over(&mut self, _view: &BotView, net: i64) {
+        self.record(net);
+    }
+}
+
+/// The strategies a seat can be given by name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BotKind {
+    Random,
+    MimicDealer,
+    NeverBust,
+    Basic,
+    HiLoIndex,
+    Martingale,
+    Paroli,
+    OneThreeTwoSix,
+}
+
+impl BotKind {
+    pub const ALL: [BotKind; 8] = [
+        BotKind::Random,
+        BotKind::MimicDealer,
+        BotKind::NeverBust,
+        BotKind::Basic,
+        BotKind::HiLoIndex,
+        BotKind::Martingale,
+        BotKind::Paroli,
+        BotKind::OneThreeTwoSix,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BotKind::Random => "random",
+            BotKind::MimicDealer => "mimic-dealer",
+            BotKind::NeverBust => "never-bust",
+            BotKind::Basic => "basic",
+            BotKind::HiLoIndex => "hi-lo-index",
+            BotKind::Martingale => "martingale",
+            BotKind::Paroli => "paroli",
+            BotKind::OneThreeTwoSix => "1-3-2-6",
+        }
+    }
+
+    /// A fresh strategy for one seat. `chart` should be generated for `rules`; it is shared
+    /// because generating one takes a while.
+    pub fn build(
+        self,
+        rules: &Rules,
+        chart: &Arc<StrategyChart>,
+        seed: u64,
+    ) -> Box<dyn BotStrategy> {
+        let chart = Arc::clone(chart);
+        match self {
+            BotKind::Random => Box::new(RandomBot::new(seed)),
+            BotKind::MimicDealer => Box::new(MimicDealerBot),
+            BotKind::NeverBust => Box::new(NeverBustBot),
+            BotKind::Basic => Box::new(BasicBot::new(chart)),
+            BotKind::HiLoIndex => Box::new(IndexBot::new(chart, rules)),
+            BotKind::Martingale => Box::new(ProgressionBot::new(Progression::Martingale, chart)),
+            BotKind::Paroli => Box::new(ProgressionBot::new(Progression::Paroli, chart)),
+            BotKind::OneThreeTwoSix => {
+                Box::new(ProgressionBot::new(Progression::OneThreeTwoSix, chart))
+            }
+        }
+    }
+}
+
+impl fmt::Display for BotKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for BotKind {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> ConfigResult<Self> {
+        let wanted = s.trim().to_ascii_lowercase().replace([' ', '_'], "-");
+        BotKind::ALL
+            .into_iter()
+            .find(|k| k.name() == wanted)
+            .ok_or_else(|| ConfigError::UnknownBotStrategy(s.to_string()))
+    }
+}
+
+/// Rounds in a row the house must win before bots remark on it.
+const DEALER_STREAK: u32 = 3;
+
+/// The bot seats at a table, each with its own strategy.
+#[derive(Default)]
+pub struct Bots {
+    seats: Vec<(Uuid, Box<dyn BotStrategy>)>,
+    clock: Duration,
+    pending: Option<(Uuid, Duration)>, // the bot thinking over a play, and when it will act
+    dealer_streak: u32,
+}
+
+impl Bots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seats a new bot player at `game`.
+    pub fn seat(
+        &mut self,
+        game: &mut Game,
+        name: &str,
+        credits: u32,
+        strategy: Box<dyn BotStrategy>,
+    ) -> GameResult<Uuid> {
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, name.to_string(), credits, true))?;
+        self.seats.push((id, strategy));
+        Ok(id)
+    }
+
+    pub fn is_bot(&self, player_id: Uuid) -> bool {
+        self.seats.iter().any(|(id, _)| *id == player_id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.seats.iter().map(|(id, _)| *id)
+    }
+
+    /// Seats, watches or leaves for every bot still at the table, then bets for those playing,
+    /// held to the table limits and rounded down to a payable amount. A bot that cannot cover
+    /// the minimum on every box, or finds no free seat, sits the round out.
+    pub fn place_bets(&mut self, game: &mut Game) -> GameResult<()> {
+        let rules = game.rules();
+        let (min, max) = (rules.min_bet, rules.max_bet);
+        let boxes = rules.boxes_per_seat() as u32;
+        let unit = match rules.payout_rounding {
+            RoundingPolicy::RejectFractional => rules.exact_bet_unit(),
+            _ => 1,
+        };
+        for (id, strategy) in &mut self.seats {
+            let Some(player) = game.player(*id) else {
+                continue;
+            };
+            let spectating = player.is_spectator;
+            match strategy.seat_choice(&BotView::new(game, *id)) {
+                SeatChoice::Leave => {
+                    game.apply(PlayerAction::new(*id, Action::Leave))?;
+                    continue;
+                }
+                SeatChoice::Watch => {
+                    if !spectating {
+                        game.apply(PlayerAction::new(*id, Action::Spectate))?;
+                    }
+                    continue;
+                }
+                SeatChoice::Play if spectating => {
+                    match game.apply(PlayerAction::new(*id, Action::Sit)) {
+                        Err(GameError::GameFull) => continue,
+                        result => result?,
+                    }
+                }
+                SeatChoice::Play => {}
+            }
+
+            let view = BotView::new(game, *id);
+            let wanted = strategy.bet(&view);
+            let amount = wanted.clamp(min, max).min(view.credits / boxes);
+            let amount = amount - amount % unit;
+            if wanted == 0 || amount < min {
+                continue;
+            }
+            // A bet the table still turns down costs this bot the round, not the others theirs.
+            let _ = game.apply(PlayerAction::new(*id, Action::Bet { amount }));
+        }
+        Ok(())
+    }
+
+    /// Puts up half the main bet on every box as insurance for each bot that wants it.
+    pub fn offer_insurance(&mut self, game: &mut Game) -> GameResult<()> {
+        for (id, strategy) in &mut self.seats {
+            let Some(seat) = game.hands().iter().find(|h| h.player_id == *id) else {
+                continue;
+            };
+            let amount = seat.bet / 2;
+            let boxes = game.rules().boxes_per_seat() as u32;
+            let view = BotView::new(game, *id);
+            if amount > 0 && amount.saturating_mul(boxes) <= view.credits && strategy.insure(&view)
+            {
+                game.apply(PlayerAction::new(*id, Action::BetInsurance { amount }))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes one play if the table is waiting on a bot. Returns whether it did.
+    pub fn take_turn(&mut self, game: &mut Game) -> GameResult<bool> {
+        let Some(current) = game.current_player() else {
+            return Ok(false);
+        };
+        let Some((_, strategy)) = self.seats.iter_mut().find(|(id, _)| *id == current) else {
+            return Ok(false);
+        };
+        let action = match game
+            .hands()
+            .iter()
+            .find(|h| h.player_id == current && !h.hands.is_finished())
+        {
+            Some(seat) => strategy.play(&BotView::new(game, current), &seat.hands),
+            None => Action::Stand,
+        };
+        game.apply(PlayerAction::new(current, action))?;
+        Ok(true)
+    }
+
+    /// Steps the table clock by `dt` and plays for the bot whose turn it is once it has
+    /// thought for long enough. Meant to be called every game loop tick; returns whether a
+    /// play was made.
+    pub fn update(&mut self, game: &mut Game, dt: Duration) -> GameResult<bool> {
+        self.clock += dt;
+        let Some(current) = game.current_player().filter(|&id| self.is_bot(id)) else {
+            self.pending = None;
+            return Ok(false);
+        };
+
+        let ready_at = match self.pending {
+            Some((id, ready_at)) if id == current => ready_at,
+            _ => {
+                let strategy = self.strategy_mut(current);
+                let delay = strategy.map_or(Duration::ZERO, |s| {
+                    s.think_time(&BotView::new(game, current))
+                });
+                self.clock + delay
+            }
+        };
+        if self.clock < ready_at {
+            self.pending = Some((current, ready_at));
+            return Ok(false);
+        }
+        self.pending = None;
+        self.take_turn(game)
+    }
+
+    /// Shows every bot still at the table the settled round, spectators included, and posts
+    /// whatever they have to say about it.
+    pub fn finish_round(&mut self, game: &mut Game) -> GameResult<()> {
+        let results = game.results();
+        if !results.is_empty() {
+            let wagered: u64 = results.iter().map(|r| r.wagered).sum();
+            let returned: u64 = results.iter().map(|r| r.returned).sum();
+            self.dealer_streak = if returned < wagered {
+                self.dealer_streak + 1
+            } else {
+                0
+            };
+        }
+
+        for (id, strategy) in &mut self.seats {
+            if game.player(*id).is_none() {
+                continue;
+            }
+            let net = game
+                .results()
+                .iter()
+                .filter(|r| r.player_id == *id)
+                .map(|r| r.returned as i64 - r.wagered as i64)
+                .sum();
+            strategy.round_over(&BotView::new(game, *id), net);
+
+            let events = round_events(game, *id, self.dealer_streak);
+            if let Some(text) = strategy.react(&events) {
+                game.apply(PlayerAction::new(*id, Action::Chat { text }))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn strategy_mut(&mut self, player_id: Uuid) -> Option<&mut Box<dyn BotStrategy>> {
+        self.seats
+            .iter_mut()
+            .find(|(id, _)| *id == player_id)
+            .map(|(_, s)| s)
+    }
+
+    /// Plays a whole round at a table with only bots seated, leaving it ready for the next bets.
+    pub fn play_round(&mut self, game: &mut Game) -> GameResult<()> {
+        self.place_bets(game)?;
+        if game.hands().is_empty() {
+            return Ok(());
+        }
+        loop {
+            match game.phase() {
+                Phase::Insurance => {
+                    self.offer_insurance(game)?;
+                    game.advance()?;
+                }
+                Phase::PlayerTurns => {
+                    if !self.take_turn(game)? {
+                        return Err(GameError::InvalidAction(
+                            "waiting on a player who is not a bot".into(),
+                        ));
+                    }
+                }
+                Phase::RoundEnd => {
+                    self.finish_round(game)?;
+                    return game.advance();
+                }
+                _ => game.advance()?,
+            }
+        }
+    }
+}
+
+fn round_events(game: &Game, player_id: Uuid, dealer_streak: u32) -> Vec<TableEvent> {
+    let mut events = Vec::new();
+    let own = game
+        .hands()
+        .iter()
+        .filter(|h| h.player_id == player_id)
+        .flat_map(|h| h.hands.hands());
+    for hand in own {
+        if hand.hand.is_blackjack() {
+            events.push(TableEvent::Natural);
+        }
+        if hand.hand.is_bust() {
+            events.push(TableEvent::Bust);
+        }
+    }
+    if game.dealer().is_bust() {
+        events.push(TableEvent::DealerBust);
+    }
+    if dealer_streak >= DEALER_STREAK {
+        events.push(TableEvent::DealerStreak(dealer_streak));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::{Rank, Suit};
+    use crate::core::rules::SurrenderRule;
+    use crate::engine::bot::betting::Wonging;
+    use crate::engine::bot::personality::{Personality, PersonalityBot};
+    use std::sync::LazyLock;
+
+    static CHART: LazyLock<Arc<StrategyChart>> =
+        LazyLock::new(|| Arc::new(StrategyChart::generate(&Rules::new())));
+
+    fn hands(ranks: &[Rank]) -> SplitHands {
+        let mut hands = SplitHands::new(10);
+        for &rank in ranks {
+            hands.deal(Card::new(rank, Suit::Hearts));
+        }
+        hands
+    }
+
+    fn view<'a>(rules: &'a Rules, dealer: &'a [Card]) -> BotView<'a> {
+        BotView {
+            rules,
+            hands: &[],
+            dealer_cards: dealer,
+            decks_remaining: 4.0,
+            shuffle_pending: false,
+            credits: 1000,
+            spectating: false,
+        }
+    }
+
+    #[test]
+    fn index_bot_deviates_with_the_count() {
+        let rules = Rules::new();
+        let ten = [Card::new(Rank::King, Suit::Clubs)];
+        let at_ten = view(&rules, &ten);
+        let sixteen = hands(&[Rank::Ten, Rank::Six]);
+        let mut bot = IndexBot::new(Arc::clone(&CHART), &rules);
+
+        bot.counter.see_all(&[Card::new(Rank::Ace, Suit::Clubs); 6]);
+        assert!(bot.true_count(&at_ten) < 0.0);
+        assert_eq!(bot.play(&at_ten, &sixteen), Action::Hit);
+        assert_eq!(bot.bet(&at_ten), rules.min_bet);
+
+        bot.counter
+            .see_all(&[Card::new(Rank::Five, Suit::Clubs); 26]);
+        // the dealer's King is in the count too
+        assert_eq!(bot.true_count(&at_ten), 4.75);
+        assert_eq!(bot.play(&at_ten, &sixteen), Action::Stand);
+        assert_eq!(
+            bot.play(&at_ten, &hands(&[Rank::Ten, Rank::Queen])),
+            Action::Stand
+        );
+        assert_eq!(bot.bet(&at_ten), rules.min_bet * 6);
+        assert!(bot.insure(&at_ten));
+
+        let five = [Card::new(Rank::Five, Suit::Clubs)];
+        let at_five = view(&rules, &five);
+        assert_eq!(
+            bot.play(&at_five, &hands(&[Rank::Ten, Rank::Queen])),
+            Action::Split
+        );
+    }
+
+    #[test]
+    fn aces_that_cannot_split_are_hit() {
+        let aces = hands(&[Rank::Ace, Rank::Ace]);
+        let six = Some(Card::new(Rank::Six, Suit::Clubs));
+        let rules = Rules::new();
+        assert_eq!(
+            chart_action(&CHART, &aces, six, &rules, 1000),
+            Action::Split
+        );
+
+        let no_splits = Rules {
+            split_limit: 0,
+            ..Rules::new()
+        };
+        assert_eq!(
+            chart_action(&CHART, &aces, six, &no_splits, 1000),
+            Action::Hit
+        );
+        // nor can a bot that cannot cover the second bet
+        assert_eq!(chart_action(&CHART, &aces, six, &rules, 5), Action::Hit);
+    }
+
+    #[test]
+    fn fab_4_surrenders_only_when_offered() {
+        let late = Rules {
+            surrender: SurrenderRule::Late,
+            ..Rules::new()
+        };
+        let ten = [Card::new(Rank::Ten, Suit::Clubs)];
+        let fourteen = hands(&[Rank::Ten, Rank::Four]);
+        let mut bot = IndexBot::new(Arc::clone(&CHART), &late);
+        bot.counter
+            .see_all(&[Card::new(Rank::Two, Suit::Clubs); 16]);
+
+        assert_eq!(bot.play(&view(&late, &ten), &fourteen), Action::Surrender);
+        let rules = Rules::new();
+        assert_eq!(bot.play(&view(&rules, &ten), &fourteen), Action::Hit);
+    }
+
+    #[test]
+    fn progressions() {
+        let sequence = |progression, results: &[i64]| {
+            let mut bot = ProgressionBot::new(progression, Arc::clone(&CHART));
+            let mut units = vec![bot.units()];
+            for &net in results {
+                bot.record(net);
+                units.push(bot.units());
+            }
+            units
+        };
+
+        assert_eq!(
+            sequence(Progression::Martingale, &[-1, -1, 0, -1, 1]),
+            [1, 2, 4, 4, 8, 1]
+        );
+        assert_eq!(
+            sequence(Progression::Paroli, &[1, 1, 1, -1]),
+            [1, 2, 4, 1, 1]
+        );
+        assert_eq!(
+            sequence(Progression::OneThreeTwoSix, &[1, 1, 1, 1, 1, -1]),
+            [1, 3, 2, 6, 1, 3, 1]
+        );
+    }
+
+    #[test]
+    fn a_table_of_bots_plays_through() {
+        let rules = Rules::new();
+        let mut game = Game::new(rules.clone(), Some(5)).unwrap();
+        let mut bots = Bots::new();
+        for (i, kind) in BotKind::ALL.into_iter().enumerate() {
+            let strategy = kind.build(&rules, &CHART, i as u64);
+            bots.seat(&mut game, kind.name(), 100_000, strategy)
+                .unwrap();
+        }
+
+        for _ in 0..300 {
+            bots.play_round(&mut game).unwrap();
+            assert_eq!(game.phase(), &Phase::Betting);
+        }
+        assert!(game.players().iter().all(|p| p.is_bot));
+        assert!(game.players().iter().any(|p| p.credits != 100_000));
+    }
+
+    #[test]
+    fn wonging_bots_back_count_and_leave_at_their_limit() {
+        let rules = Rules::new();
+        let mut game = Game::new(rules.clone(), Some(9)).unwrap();
+        let mut bots = Bots::new();
+        bots.seat(
+            &mut game,
+            "basic",
+            100_000,
+            Box::new(BasicBot::new(Arc::clone(&CHART))),
+        )
+        .unwrap();
+        let mut plan = BettingPlan::default();
+        plan.wonging = Some(Wonging {
+            enter_at: 1.0,
+            exit_below: 0.0,
+        });
+        plan.limits.stop_loss = Some(200);
+        let wonger = IndexBot::with_plan(Arc::clone(&CHART), &rules, plan);
+        let id = bots
+            .seat(&mut game, "wonger", 1000, Box::new(wonger))
+            .unwrap();
+
+        let (mut watched, mut played) = (0, 0);
+        for _ in 0..2000 {
+            bots.play_round(&mut game).unwrap();
+            match game.player(id) {
+                Some(p) if p.is_spectator => watched += 1,
+                Some(_) => played += 1,
+                None => break,
+            }
+        }
+        assert!(watched > 0 && played > 0);
+        // Seeded, so the session runs the same way every time: up and down, then out at the
+        // stop-loss.
+        assert!(game.player(id).is_none());
+    }
+
+    /// Runs `rounds` rounds of a tourist bot at 16 ms ticks, returning the chat and the ticks
+    /// each play took.
+    fn paced_table(seed: u64, rounds: usize) -> (Vec<String>, Vec<u32>) {
+        let mut game = Game::new(Rules::new(), Some(seed)).unwrap();
+        let mut bots = Bots::new();
+        let tourist = PersonalityBot::new(
+            Box::new(BasicBot::new(Arc::clone(&CHART))),
+            Personality::tourist(),
+            seed,
+        );
+        bots.seat(&mut game, "tourist", 100_000, Box::new(tourist))
+            .unwrap();
+
+        let tick = Duration::from_millis(16);
+        let mut waits = Vec::new();
+        for _ in 0..rounds {
+            bots.place_bets(&mut game).unwrap();
+            let mut ticks = 0;
+            loop {
+                match game.phase() {
+                    Phase::PlayerTurns => {
+                        ticks += 1;
+                        if bots.update(&mut game, tick).unwrap() {
+                            waits.push(ticks);
+                            ticks = 0;
+                        }
+                    }
+                    Phase::RoundEnd => {
+                        bots.finish_round(&mut game).unwrap();
+                        game.advance().unwrap();
+                        break;
+                    }
+                    _ => game.advance().unwrap(),
+                }
+            }
+        }
+        let chat = game.chat().iter().map(|m| m.text.clone()).collect();
+        (chat, waits)
+    }
+
+    #[test]
+    fn personalities_take_their_time_and_talk() {
+        let (chat, waits) = paced_table(4, 60);
+        // a tourist thinks for one to four seconds, in 16 ms ticks
+        assert!(!waits.is_empty());
+        assert!(waits.iter().all(|&t| (62..=251).contains(&t)), "{waits:?}");
+        assert!(!chat.is_empty());
+        assert_eq!(paced_table(4, 60), (chat, waits));
+    }
+
+    #[test]
+    fn waits_for_humans() {
+        let rules = Rules::new();
+        let mut game = Game::new(rules.clone(), Some(5)).unwrap();
+        let human = Uuid::new_v4();
+        game.add_player(Player::new(human, "human".into(), 1000, false))
+            .unwrap();
+        game.apply(PlayerAction::new(human, Action::Bet { amount: 10 }))
+            .unwrap();
+        let mut bots = Bots::new();
+        bots.seat(&mut game, "bot", 1000, Box::new(MimicDealerBot))
+            .unwrap();
+
+        // human 10 and 7, bot 10 and 8, dealer 9 up: the human acts first
+        let cards = [
+            Rank::Ten,
+            Rank::Ten,
+            Rank::Nine,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Eight,
+        ];
+        game.shoe_mut()
+            .inject(&cards.map(|r| Card::new(r, Suit::Spades)))
+            .unwrap();
+
+        match bots.play_round(&mut game) {
+            Err(GameError::InvalidAction(reason)) => {
+                assert_eq!(reason, "waiting on a player who is not a bot")
+            }
+            other => panic!("expected to wait on the human, got {other:?}"),
+        }
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+        assert_eq!(game.current_player(), Some(human));
+        assert!(!bots.is_bot(human));
+    }
+
+    /// Bets a fixed amount and stands.
+    struct FixedBot(u32);
+
+    impl BotStrategy for FixedBot {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn bet(&mut self, _view: &BotView) -> u32 {
+            self.0
+        }
+
+        fn play(&mut self, _view: &BotView, _hands: &SplitHands) -> Action {
+            Action::Stand
+        }
+    }
+
+    #[test]
+    fn plays_only_what_the_credits_cover() {
+        let rules = Rules::new();
+        let six = [Card::new(Rank::Six, Suit::Clubs)];
+        let mut broke = view(&rules, &six);
+        broke.credits = 0;
+        let eleven = hands(&[Rank::Six, Rank::Five]);
+        let eights = hands(&[Rank::Eight, Rank::Eight]);
+
+        assert_eq!(
+            legal_actions(&eleven, &rules, 0),
+            [Action::Stand, Action::Hit]
+        );
+        assert!(legal_actions(&eights, &rules, 10).contains(&Action::Split));
+        let mut bot = BasicBot::new(Arc::clone(&CHART));
+        assert_eq!(bot.play(&view(&rules, &six), &eleven), Action::Double);
+        assert_eq!(bot.play(&broke, &eleven), Action::Hit);
+        assert_eq!(bot.play(&broke, &eights), Action::Stand);
+
+        // bot 6 and 5 against a dealer 6, holding nothing back after the bet
+        let mut game = Game::new(rules, Some(2)).unwrap();
+        let mut bots = Bots::new();
+        bots.seat(
+            &mut game,
+            "bot",
+            10,
+            Box::new(BasicBot::new(Arc::clone(&CHART))),
+        )
+        .unwrap();
+        let cards = [Rank::Six, Rank::Six, Rank::Five, Rank::Ten];
+        game.shoe_mut()
+            .inject(&cards.map(|r| Card::new(r, Suit::Spades)))
+            .unwrap();
+        bots.play_round(&mut game).unwrap();
+        assert_eq!(game.phase(), &Phase::Betting);
+    }
+
+    #[test]
+    fn bets_fit_every_box_and_the_payout_unit() {
+        let switch = Rules::blackjack_switch();
+        let mut game = Game::new(switch, Some(3)).unwrap();
+        let mut bots = Bots::new();
+        let short = bots
+            .seat(&mut game, "short", 15, Box::new(FixedBot(10)))
+            .unwrap();
+        let flush = bots
+            .seat(&mut game, "flush", 1000, Box::new(FixedBot(10)))
+            .unwrap();
+        bots.place_bets(&mut game).unwrap();
+        assert!(game.hands().iter().all(|h| h.player_id == flush));
+        assert_eq!(game.hands().len(), 2);
+        assert_eq!(game.player(short).unwrap().credits, 15);
+
+        let exact = Rules {
+            payout_rounding: RoundingPolicy::RejectFractional,
+            ..Rules::new()
+        };
+        let mut game = Game::new(exact, Some(3)).unwrap();
+        let mut bots = Bots::new();
+        for _ in 0..2 {
+            bots.seat(&mut game, "odd", 1000, Box::new(FixedBot(25)))
+                .unwrap();
+        }
+        bots.place_bets(&mut game).unwrap();
+        assert_eq!(game.hands().len(), 2);
+        assert!(game.hands().iter().all(|h| h.bet == 24));
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for kind in BotKind::ALL {
+            assert_eq!(kind.name().parse::<BotKind>().unwrap(), kind);
+        }
+        assert!("card-sharp".parse::<BotKind>().is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Bet sizing and bankroll rules for counting bots. Amounts come out in credits; the table
+//! limits are applied by [`Bots`](super::Bots) when the bet is placed.
+
+use crate::core::house_edge::house_edge;
+use crate::core::rules::Rules;
+use crate::engine::bot::SeatChoice;
+use crate::error::{ConfigError, ConfigResult};
+
+/// Player advantage per point of Hi-Lo true count, the usual rule of thumb.
+const EDGE_PER_TRUE_COUNT: f64 = 0.005;
+
+/// Variance of a blackjack round in squared initial bets.
+const ROUND_VARIANCE: f64 = 1.33;
+
+/// Units bet at each true count: every step applies from its count up to the next one, and the
+/// first step's units are also bet below it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BetSpread {
+    steps: Vec<(i8, u32)>,
+}
+
+impl BetSpread {
+    pub fn new(steps: Vec<(i8, u32)>) -> ConfigResult<Self> {
+        if steps.is_empty() || steps.iter().any(|&(_, units)| units == 0) {
+            return Err(ConfigError::Other(
+                "bet spread needs at least one step, each of one unit or more".into(),
+            ));
+        }
+        if steps.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(ConfigError::Other(
+                "bet spread counts must increase step by step".into(),
+            ));
+        }
+        Ok(Self { steps })
+    }
+
+    /// A common shoe ramp: one unit up to +2, then 2, 4, 6 and 8 units from +2 to +5.
+    pub fn one_to_eight() -> Self {
+        Self {
+            steps: vec![(1, 1), (2, 2), (3, 4), (4, 6), (5, 8)],
+        }
+    }
+
+    pub fn units(&self, true_count: f64) -> u32 {
+        self.steps
+            .iter()
+            .rev()
+            .find(|&&(count, _)| true_count >= count as f64)
+            .map_or(self.steps[0].1, |&(_, units)| units)
+    }
+
+    pub fn steps(&self) -> &[(i8, u32)] {
+        &self.steps
+    }
+}
+
+/// How big a bet is.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BetSizing {
+    Spread(BetSpread),       // in units of the table minimum
+    Kelly { fraction: f64 }, // of the full Kelly bet on the current credits
+}
+
+/// Back-counting: watch from a spectator seat until the count reaches `enter_at`, play until
+/// it falls below `exit_below`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Wonging {
+    pub enter_at: f64,
+    pub exit_below: f64,
+}
+
+/// When to walk away, measured from the credits held at the first bet of the session.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionLimits {
+    pub stop_loss: Option<u32>,
+    pub win_goal: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BettingPlan {
+    pub sizing: BetSizing,
+    pub wonging: Option<Wonging>,
+    pub limits: SessionLimits,
+    starting_credits: Option<u32>,
+}
+
+impl BettingPlan {
+    pub fn new(sizing: BetSizing) -> Self {
+        Self {
+            sizing,
+            wonging: None,
+            limits: SessionLimits::default(),
+            starting_credits: None,
+        }
+    }
+
+    /// Whether to bet this round, sit it out as a spectator or leave for good.
+    pub fn seat_choice(&mut self, true_count: f64, credits: u32, spectating: bool) -> SeatChoice {
+        let start = *self.starting_credits.get_or_insert(credits);
+        let lost = self
+            .limits
+            .stop_loss
+            .is_some_and(|l| start.saturating_sub(credits) >= l);
+        let won = self
+            .limits
+            .win_goal
+            .is_some_and(|g| credits.saturating_sub(start) >= g);
+        if lost || won {
+            return SeatChoice::Leave;
+        }
+
+        match self.wonging {
+            Some(w) if spectating && true_count < w.enter_at => SeatChoice::Watch,
+            Some(w) if !spectating && true_count < w.exit_below => SeatChoice::Watch,
+            _ => SeatChoice::Play,
+        }
+    }
+
+    /// The bet in credits at `true_count`, before table limits.
+    pub fn wager(&self, true_count: f64, credits: u32, rules: &Rules) -> u32 {
+        let unit = rules.min_bet.max(1);
+        match &self.sizing {
+            BetSizing::Spread(spread) => unit.saturating_mul(spread.units(true_count)),
+            BetSizing::Kelly { fraction } => {
+                let edge = true_count * EDGE_PER_TRUE_COUNT - house_edge(rules) / 100.0;
+                if edge <= 0.0 {
+                    return unit;
+                }
+                let kelly = fraction * credits as f64 * edge / ROUND_VARIANCE;
+                // Whole units, so the bet stays payable at tables that reject fractions.
+                ((kelly / unit as f64) as u32).max(1).saturating_mul(unit)
+            }
+        }
+    }
+}
+
+impl Default for BettingPlan {
+    fn default() -> Self {
+        Self::new(BetSizing::Spread(BetSpread::one_to_eight()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_step_with_the_count() {
+        let spread = BetSpread::one_to_eight();
+        assert_eq!(spread.units(-3.0), 1);
+        assert_eq!(spread.units(1.9), 1);
+        assert_eq!(spread.units(2.0), 2);
+        assert_eq!(spread.units(4.75), 6);
+        assert_eq!(spread.units(12.0), 8);
+
+        assert!(BetSpread::new(vec![]).is_err());
+        assert!(BetSpread::new(vec![(2, 1), (1, 2)]).is_err());
+        assert!(BetSpread::new(vec![(0, 0)]).is_err());
+    }
+
+    #[test]
+    fn kelly_bets_grow_with_edge_and_bankroll() {
+        let rules = Rules::new();
+        let plan = BettingPlan::new(BetSizing::Kelly { fraction: 0.5 });
+        assert_eq!(plan.wager(-2.0, 100_000, &rules), rules.min_bet);
+
+        let small = plan.wager(4.0, 10_000, &rules);
+        let large = plan.wager(4.0, 100_000, &rules);
+        let hotter = plan.wager(8.0, 100_000, &rules);
+        assert!(small < large && large < hotter);
+        assert!(large.is_multiple_of(rules.min_bet));
+    }
+
+    #[test]
+    fn wonging_in_and_out() {
+        let mut plan = BettingPlan {
+            wonging: Some(Wonging {
+                enter_at: 2.0,
+                exit_below: 0.0,
+            }),
+            ..BettingPlan::default()
+        };
+        assert_eq!(plan.seat_choice(1.0, 1000, true), SeatChoice::Watch);
+        assert_eq!(plan.seat_choice(2.5, 1000, true), SeatChoice::Play);
+        assert_eq!(plan.seat_choice(0.5, 1000, false), SeatChoice::Play);
+        assert_eq!(plan.seat_choice(-0.5, 1000, false), SeatChoice::Watch);
+    }
+
+    #[test]
+    fn session_limits() {
+        let mut plan = BettingPlan {
+            limits: SessionLimits {
+                stop_loss: Some(300),
+                win_goal: Some(500),
+            },
+            ..BettingPlan::default()
+        };
+        assert_eq!(plan.seat_choice(0.0, 1000, false), SeatChoice::Play);
+        assert_eq!(plan.seat_choice(0.0, 1400, false), SeatChoice::Play);
+        assert_eq!(plan.seat_choice(0.0, 1500, false), SeatChoice::Leave);
+        assert_eq!(plan.seat_choice(0.0, 700, false), SeatChoice::Leave);
+    }
+}
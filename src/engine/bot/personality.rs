@@ -0,0 +1,235 @@
+//! Table manners for bots: how long they take over a decision, how often they misplay on
+//! purpose and what they say when something happens. All of it comes from a seeded rng, so a
+//! seeded table plays out the same way every time.
+
+use crate::core::split::SplitHands;
+use crate::engine::bot::{BotStrategy, BotView, SeatChoice, legal_actions};
+use crate::types::action::Action;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::time::Duration;
+
+/// Something at the table worth a remark, most notable first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TableEvent {
+    Natural,
+    Bust,
+    DealerBust,
+    DealerStreak(u32), // rounds in a row the house has come out ahead
+}
+
+impl TableEvent {
+    fn lines(self) -> &'static [&'static str] {
+        match self {
+            TableEvent::Natural => &["Blackjack!", "Now that's more like it.", "Paid at last."],
+            TableEvent::Bust => &["Too many.", "Should have stood.", "Ouch."],
+            TableEvent::DealerBust => &["Dealer busts!", "There it goes!", "Love to see it."],
+            TableEvent::DealerStreak(_) => &[
+                "This dealer is on fire.",
+                "Can we get a new dealer?",
+                "The house is running hot tonight.",
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Personality {
+    pub think_min: Duration,
+    pub think_max: Duration,
+    pub error_rate: f64, // share of plays deliberately made wrong
+    pub chattiness: f64, // chance of a remark when something happens
+}
+
+impl Personality {
+    /// Instant, flawless and silent: the bare strategy.
+    pub fn machine() -> Self {
+        Self {
+            think_min: Duration::ZERO,
+            think_max: Duration::ZERO,
+            error_rate: 0.0,
+            chattiness: 0.0,
+        }
+    }
+
+    pub fn regular() -> Self {
+        Self {
+            think_min: Duration::from_millis(600),
+            think_max: Duration::from_millis(1800),
+            error_rate: 0.02,
+            chattiness: 0.25,
+        }
+    }
+
+    pub fn tourist() -> Self {
+        Self {
+            think_min: Duration::from_millis(1000),
+            think_max: Duration::from_millis(4000),
+            error_rate: 0.1,
+            chattiness: 0.6,
+        }
+    }
+}
+
+/// Wraps a strategy in a personality.
+pub struct PersonalityBot {
+    inner: Box<dyn BotStrategy>,
+    personality: Personality,
+    rng: ChaCha8Rng,
+}
+
+impl PersonalityBot {
+    pub fn new(inner: Box<dyn BotStrategy>, personality: Personality, seed: u64) -> Self {
+        Self {
+            inner,
+            personality,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        self.rng.random_bool(probability.clamp(0.0, 1.0))
+    }
+}
+
+impl BotStrategy for PersonalityBot {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn seat_choice(&mut self, view: &BotView) -> SeatChoice {
+        self.inner.seat_choice(view)
+    }
+
+    fn bet(&mut self, view: &BotView) -> u32 {
+        self.inner.bet(view)
+    }
+
+    /// The strategy's play, or now and then some other legal one.
+    fn play(&mut self, view: &BotView, hands: &SplitHands) -> Action {
+        let intended = self.inner.play(view, hands);
+        if !self.chance(self.personality.error_rate) {
+            return intended;
+        }
+        let mistakes: Vec<Action> = legal_actions(hands, view.rules, view.credits)
+            .into_iter()
+            .filter(|a| *a != intended)
+            .collect();
+        mistakes.choose(&mut self.rng).cloned().unwrap_or(intended)
+    }
+
+    fn insure(&mut self, view: &BotView) -> bool {
+        self.inner.insure(view)
+    }
+
+    fn think_time(&mut self, _view: &BotView) -> Duration {
+        let (min, max) = (self.personality.think_min, self.personality.think_max);
+        if max <= min {
+            return min;
+        }
+        let nanos = self.rng.random_range(min.as_nanos()..=max.as_nanos());
+        Duration::from_nanos(nanos as u64)
+    }
+
+    fn react(&mut self, events: &[TableEvent]) -> Option<String> {
+        let event = events.iter().min().copied()?;
+        if !self.chance(self.personality.chattiness) {
+            return None;
+        }
+        event
+            .lines()
+            .choose(&mut self.rng)
+            .map(|line| line.to_string())
+    }
+
+    fn round_over(&mut self, view: &BotView, net: i64) {
+        self.inner.round_over(view, net);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::{Card, Rank, Suit};
+    use crate::core::rules::Rules;
+    use crate::engine::bot::MimicDealerBot;
+
+    fn bot(personality: Personality, seed: u64) -> PersonalityBot {
+        PersonalityBot::new(Box::new(MimicDealerBot), personality, seed)
+    }
+
+    fn twelve() -> SplitHands {
+        let mut hands = SplitHands::new(10);
+        hands.deal(Card::new(Rank::Ten, Suit::Hearts));
+        hands.deal(Card::new(Rank::Two, Suit::Hearts));
+        hands
+    }
+
+    fn view(rules: &Rules) -> BotView<'_> {
+        BotView {
+            rules,
+            hands: &[],
+            dealer_cards: &[],
+            decks_remaining: 6.0,
+            shuffle_pending: false,
+            credits: 1000,
+            spectating: false,
+        }
+    }
+
+    #[test]
+    fn mistakes_follow_the_error_rate() {
+        let rules = Rules::new();
+        let mut sloppy = bot(
+            Personality {
+                error_rate: 0.25,
+                ..Personality::machine()
+            },
+            3,
+        );
+        let wrong = (0..4000)
+            .filter(|_| sloppy.play(&view(&rules), &twelve()) != Action::Hit)
+            .count();
+        assert!((800..1200).contains(&wrong), "{wrong}");
+
+        let mut machine = bot(Personality::machine(), 3);
+        assert!((0..100).all(|_| machine.play(&view(&rules), &twelve()) == Action::Hit));
+    }
+
+    #[test]
+    fn think_time_stays_in_range_and_repeats_under_a_seed() {
+        let rules = Rules::new();
+        let personality = Personality::regular();
+        let (mut a, mut b) = (bot(personality.clone(), 8), bot(personality.clone(), 8));
+        for _ in 0..100 {
+            let delay = a.think_time(&view(&rules));
+            assert!((personality.think_min..=personality.think_max).contains(&delay));
+            assert_eq!(delay, b.think_time(&view(&rules)));
+        }
+        assert_eq!(
+            bot(Personality::machine(), 1).think_time(&view(&rules)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn remarks_on_the_most_notable_event() {
+        let mut chatty = bot(
+            Personality {
+                chattiness: 1.0,
+                ..Personality::machine()
+            },
+            5,
+        );
+        let line = chatty
+            .react(&[TableEvent::DealerStreak(4), TableEvent::Bust])
+            .unwrap();
+        assert!(TableEvent::Bust.lines().contains(&line.as_str()));
+        assert_eq!(chatty.react(&[]), None);
+        assert_eq!(
+            bot(Personality::machine(), 5).react(&[TableEvent::Natural]),
+            None
+        );
+    }
+}
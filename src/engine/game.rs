@@ -1 +1,1817 @@
+use crate::core::card::{Card, Rank};
+use crate::core::hand::Hand;
+use crate::core::payout::{RoundingPolicy, calculate_insurance_payout};
+use crate::core::progressive::Jackpots;
+use crate::core::ranking::FIVE_CARD_TRICK;
+use crate::core::rules::{Rules, ShuffleMode, Variant};
+use crate::core::shoe::Shoe;
+use crate::core::side_bet::{Resolution, SideBet, SideBetCards, SideBetOutcome, SideBetRegistry};
+use crate::engine::state::TableSnapshot;
+use crate::error::{ConfigResult, GameError, GameResult};
+use crate::types::action::{Action, PlayerAction};
+use crate::types::chat::ChatMessage;
+use crate::types::phase::Phase;
+use crate::types::player::{Player, PlayerHand};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
 
+pub const MAX_SEATS: usize = 8;
+
+/// Chat lines kept for the snapshot; older ones scroll away.
+pub const CHAT_HISTORY: usize = 50;
+
+pub const MAX_CHAT_LENGTH: usize = 200;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundResult {
+    pub player_id: Uuid,
+    pub wagered: u64,
+    pub returned: u64,
+}
+
+/// Server-side table: owns the shoe, seats and dealer, and walks a round through its phases.
+///
+/// Player input arrives through [`Game::apply`]; phases that need no input (dealing, the
+/// dealer's turn, payout) are stepped with [`Game::advance`].
+#[derive(Clone, Debug)]
+pub struct Game {
+    rules: Rules,
+    shoe: Shoe,
+    phase: Phase,
+    players: Vec<Player>,
+    hands: Vec<PlayerHand>,
+    dealer: Hand,
+    turn: usize,
+    results: Vec<RoundResult>,
+    rng: ChaCha8Rng, // table randomness outside the shoe, e.g. side bet wheels
+    side_bets: SideBetRegistry,
+    jackpots: Jackpots,
+    chat: Vec<ChatMessage>,
+}
+
+impl Game {
+    pub fn new(rules: Rules, seed: Option<u64>) -> ConfigResult<Self> {
+        rules.validate()?;
+        let shoe = build_shoe(&rules, seed)?;
+        let rng = match seed {
+            Some(s) => ChaCha8Rng::seed_from_u64(s.wrapping_add(1)),
+            None => ChaCha8Rng::from_os_rng(),
+        };
+
+        let side_bets = SideBetRegistry::from_rules(&rules);
+
+        let mut game = Self {
+            rules,
+            shoe,
+            phase: Phase::Betting,
+            players: Vec::new(),
+            hands: Vec::new(),
+            dealer: Hand::new(),
+            turn: 0,
+            results: Vec::new(),
+            rng,
+            side_bets,
+            jackpots: Jackpots::default(),
+            chat: Vec::new(),
+        };
+        game.open_jackpots();
+        Ok(game)
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn player(&self, id: Uuid) -> Option<&Player> {
+        self.players.iter().find(|p| p.id == id)
+    }
+
+    pub fn hands(&self) -> &[PlayerHand] {
+        &self.hands
+    }
+
+    pub fn dealer(&self) -> &Hand {
+        &self.dealer
+    }
+
+    pub fn dealer_upcard(&self) -> Option<Card> {
+        self.dealer.cards().first().copied()
+    }
+
+    pub fn results(&self) -> &[RoundResult] {
+        &self.results
+    }
+
+    pub fn chat(&self) -> &[ChatMessage] {
+        &self.chat
+    }
+
+    /// Replaces the rules between rounds. Only the host may, and only rules that validate; the
+    /// shoe is rebuilt and side bets re-registered, keeping any the house added itself.
+    pub fn change_rules(&mut self, host_id: Uuid, rules: Rules) -> GameResult<()> {
+        if !self.player(host_id).is_some_and(|p| p.is_host) {
+            return Err(GameError::InvalidAction(
+                "only the host can change the rules".into(),
+            ));
+        }
+        if self.phase != Phase::Betting || !self.hands.is_empty() {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+        rules.validate()?;
+
+        self.shoe = build_shoe(&rules, Some(self.rng.next_u64()))?;
+        self.side_bets = self.side_bets.rebuild(&rules);
+        self.rules = rules;
+        self.open_jackpots();
+        Ok(())
+    }
+
+    pub fn jackpots(&self) -> &Jackpots {
+        &self.jackpots
+    }
+
+    /// Restores meters carried over from a saved session.
+    pub fn set_jackpots(&mut self, jackpots: Jackpots) {
+        self.jackpots = jackpots;
+        self.open_jackpots();
+    }
+
+    pub fn side_bets(&self) -> &SideBetRegistry {
+        &self.side_bets
+    }
+
+    /// Offers a house side bet next to the built-ins enabled in the rules.
+    pub fn register_side_bet(&mut self, bet: Arc<dyn SideBet>) {
+        self.side_bets.register(bet);
+        self.open_jackpots();
+    }
+
+    pub fn shoe(&self) -> &Shoe {
+        &self.shoe
+    }
+
+    pub fn shoe_mut(&mut self) -> &mut Shoe {
+        &mut self.shoe
+    }
+
+    /// The player whose decision the table is waiting on.
+    pub fn current_player(&self) -> Option<Uuid> {
+        if self.phase != Phase::PlayerTurns {
+            return None;
+        }
+        self.hands.get(self.turn).map(|h| h.player_id)
+    }
+
+    /// The dealer's cards a seat can see: the upcard, or all of them once the hole card is
+    /// turned over (or never hidden). Pontoon's banker shows nothing.
+    pub fn visible_dealer_cards(&self) -> &[Card] {
+        let revealed = self.rules.dealer_cards_exposed()
+            || matches!(
+                self.phase,
+                Phase::DealerTurn | Phase::Payout | Phase::RoundEnd
+            );
+        let shown = if revealed {
+            self.dealer.cards().len()
+        } else if self.rules.dealer_cards_concealed() {
+            0
+        } else {
+            1
+        };
+        &self.dealer.cards()[..shown.min(self.dealer.cards().len())]
+    }
+
+    pub fn snapshot(&self) -> TableSnapshot {
+        let dealer_cards = self.visible_dealer_cards().to_vec();
+
+        TableSnapshot {
+            phase: self.phase.clone(),
+            players: self.players.clone(),
+            hands: self.hands.clone(),
+            dealer_hidden_cards: self.dealer.cards().len() - dealer_cards.len(),
+            dealer_cards,
+            current_player: self.current_player(),
+            jackpots: self.jackpots.clone(),
+            chat: self.chat.clone(),
+        }
+    }
+
+    pub fn add_player(&mut self, player: Player) -> GameResult<()> {
+        if self.players.iter().filter(|p| !p.is_spectator).count() >= MAX_SEATS {
+            return Err(GameError::GameFull);
+        }
+        self.players.push(player);
+        Ok(())
+    }
+
+    pub fn apply(&mut self, action: PlayerAction) -> GameResult<()> {
+        let PlayerAction { player_id, action } = action;
+        if self.player(player_id).is_none() {
+            return Err(GameError::PlayerNotFound(player_id));
+        }
+
+        match action {
+            Action::Leave => self.leave(player_id),
+            Action::Spectate => {
+                self.player_mut(player_id)?.is_spectator = true;
+                Ok(())
+            }
+            Action::Sit => self.sit(player_id),
+            Action::Chat { text } => self.say(player_id, &text),
+            Action::Bet { amount } => self.place_bet(player_id, amount),
+            Action::BetSide { id, amount } => self.place_side_bet(player_id, &id, amount),
+            Action::BetInsurance { amount } => self.insure(player_id, amount),
+            Action::EvenMoney => self.take_even_money(player_id),
+            Action::Hit | Action::Stand | Action::Double | Action::Split | Action::Buy => {
+                self.play(player_id, action)
+            }
+            Action::Surrender => self.surrender(player_id),
+            Action::Switch => self.switch(player_id),
+        }
+    }
+
+    /// Steps the phases that run without player input.
+    pub fn advance(&mut self) -> GameResult<()> {
+        match self.phase {
+            Phase::Betting => self.deal_round(),
+            Phase::Dealing if self.rules.variant == Variant::Switch => {
+                self.phase = Phase::Switch;
+                Ok(())
+            }
+            Phase::Dealing | Phase::Switch => {
+                if self.offers_insurance() {
+                    self.phase = Phase::Insurance;
+                } else {
+                    self.peek();
+                }
+                Ok(())
+            }
+            Phase::Insurance => {
+                self.settle_side_offers();
+                self.peek();
+                Ok(())
+            }
+            Phase::PlayerTurns => Err(GameError::WrongPhase(Phase::PlayerTurns)),
+            Phase::DealerTurn => self.play_dealer(),
+            Phase::Payout => {
+                self.settle();
+                Ok(())
+            }
+            Phase::RoundEnd => {
+                let mut discards: Vec<Card> = self
+                    .hands
+                    .iter()
+                    .flat_map(|h| h.hands.hands())
+                    .flat_map(|s| s.hand.cards().iter().copied())
+                    .collect();
+                discards.extend_from_slice(self.dealer.cards());
+                self.shoe.return_discards(&discards);
+
+                self.hands.clear();
+                self.dealer = Hand::new();
+                self.turn = 0;
+                self.phase = Phase::Betting;
+                Ok(())
+            }
+        }
+    }
+
+    fn player_mut(&mut self, id: Uuid) -> GameResult<&mut Player> {
+        self.players
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or(GameError::PlayerNotFound(id))
+    }
+
+    /// The player's first box, which carries the seat's side bets.
+    fn seat(&self, player_id: Uuid) -> GameResult<&PlayerHand> {
+        self.hands
+            .iter()
+            .find(|h| h.player_id == player_id)
+            .ok_or(GameError::PlayerNotFound(player_id))
+    }
+
+    fn seat_mut(&mut self, player_id: Uuid) -> GameResult<&mut PlayerHand> {
+        self.hands
+            .iter_mut()
+            .find(|h| h.player_id == player_id)
+            .ok_or(GameError::PlayerNotFound(player_id))
+    }
+
+    /// Every box the player has in play: two for a Switch seat, otherwise one.
+    fn boxes(&self, player_id: Uuid) -> GameResult<Vec<usize>> {
+        let boxes: Vec<usize> = (0..self.hands.len())
+            .filter(|&i| self.hands[i].player_id == player_id)
+            .collect();
+        if boxes.is_empty() {
+            return Err(GameError::PlayerNotFound(player_id));
+        }
+        Ok(boxes)
+    }
+
+    fn say(&mut self, player_id: Uuid, text: &str) -> GameResult<()> {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_LENGTH {
+            return Err(GameError::InvalidAction(format!(
+                "chat lines must be 1 to {MAX_CHAT_LENGTH} characters"
+            )));
+        }
+        let name = self.player_mut(player_id)?.name.clone();
+        self.chat.push(ChatMessage {
+            player_id,
+            name,
+            text: text.to_string(),
+        });
+        if self.chat.len() > CHAT_HISTORY {
+            self.chat.remove(0);
+        }
+        Ok(())
+    }
+
+    fn sit(&mut self, player_id: Uuid) -> GameResult<()> {
+        if !self.player_mut(player_id)?.is_spectator {
+            return Ok(());
+        }
+        if self.players.iter().filter(|p| !p.is_spectator).count() >= MAX_SEATS {
+            return Err(GameError::GameFull);
+        }
+        self.player_mut(player_id)?.is_spectator = false;
+        Ok(())
+    }
+
+    fn leave(&mut self, player_id: Uuid) -> GameResult<()> {
+        if self.phase == Phase::Betting {
+            // Nothing is dealt yet, so the boxes are simply taken off the layout.
+            self.hands.retain(|h| h.player_id != player_id);
+        } else {
+            // Hands already in play are stood; their winnings go nowhere once the seat is empty.
+            for seat in self.hands.iter_mut().filter(|h| h.player_id == player_id) {
+                while !seat.hands.is_finished() {
+                    seat.hands.stand()?;
+                }
+            }
+            if self.phase == Phase::PlayerTurns {
+                self.skip_finished();
+            }
+        }
+        self.players.retain(|p| p.id != player_id);
+        Ok(())
+    }
+
+    fn place_bet(&mut self, player_id: Uuid, amount: u32) -> GameResult<()> {
+        if self.phase != Phase::Betting {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+        if self.hands.iter().any(|h| h.player_id == player_id) {
+            return Err(GameError::InvalidAction("bet already placed".into()));
+        }
+        if amount < self.rules.min_bet {
+            return Err(GameError::BetTooLow {
+                bet: amount,
+                min: self.rules.min_bet,
+            });
+        }
+        if amount > self.rules.max_bet {
+            return Err(GameError::BetTooHigh {
+                bet: amount,
+                max: self.rules.max_bet,
+            });
+        }
+        let unit = self.rules.exact_bet_unit();
+        if self.rules.payout_rounding == RoundingPolicy::RejectFractional
+            && !amount.is_multiple_of(unit)
+        {
+            return Err(GameError::BetNotDivisible { bet: amount, unit });
+        }
+
+        // Switch seats play two boxes, each carrying the full bet.
+        let boxes = self.rules.boxes_per_seat();
+        let player = self.player_mut(player_id)?;
+        if player.is_spectator {
+            return Err(GameError::InvalidAction("spectators cannot bet".into()));
+        }
+        charge(player, amount.saturating_mul(boxes as u32))?;
+        for _ in 0..boxes {
+            self.hands.push(PlayerHand::new(player_id, amount));
+        }
+        Ok(())
+    }
+
+    fn place_side_bet(&mut self, player_id: Uuid, id: &str, amount: u32) -> GameResult<()> {
+        if self.phase != Phase::Betting {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+        let Some(bet) = self.side_bets.get(id) else {
+            return Err(GameError::InvalidAction(format!("{id} is not offered")));
+        };
+        let progressive = bet.is_progressive();
+
+        let seat = self
+            .seat(player_id)
+            .map_err(|_| GameError::InvalidAction("place a main bet first".into()))?;
+        let main_bet = seat.bet;
+        if amount == 0 || amount > main_bet {
+            return Err(GameError::InvalidAction(format!(
+                "side bets must be between 1 and {main_bet}"
+            )));
+        }
+        if seat.side_bets.contains_key(id) {
+            return Err(GameError::InvalidAction("side bet already placed".into()));
+        }
+
+        charge_player(&mut self.players, player_id, amount)?;
+        self.seat_mut(player_id)?
+            .side_bets
+            .insert(id.to_string(), amount);
+        if progressive && let Some(pool) = self.jackpots.get_mut(id) {
+            pool.contribute(amount);
+        }
+        Ok(())
+    }
+
+    fn open_jackpots(&mut self) {
+        for bet in self.side_bets.iter().filter(|b| b.is_progressive()) {
+            self.jackpots.open(
+                bet.id(),
+                self.rules.jackpot_seed,
+                self.rules.jackpot_contribution_percent,
+            );
+        }
+    }
+
+    fn deal_round(&mut self) -> GameResult<()> {
+        if self.hands.is_empty() {
+            return Err(GameError::InvalidAction("no bets placed".into()));
+        }
+        if self.shoe.needs_reshuffle() {
+            self.shoe.shuffle();
+        }
+
+        self.results.clear();
+        for round in 0..2 {
+            for i in 0..self.hands.len() {
+                let card = self.shoe.draw()?;
+                self.hands[i].hands.deal(card);
+            }
+            if round == 0 || self.rules.dealer_has_hole_card() {
+                let card = self.shoe.draw()?;
+                self.dealer.add_card(card);
+            }
+        }
+
+        for (player_id, wagered, returned) in self.settle_side_bets(Resolution::OnDeal) {
+            self.record(player_id, wagered, returned);
+        }
+        self.phase = Phase::Dealing;
+        Ok(())
+    }
+
+    /// Settles every side bet that resolves at `resolution`: those that only look at the first
+    /// cards go as soon as the deal is out, the rest once the dealer has finished.
+    fn settle_side_bets(&mut self, resolution: Resolution) -> Vec<(Uuid, u64, u64)> {
+        let Some(upcard) = self.dealer_upcard() else {
+            return Vec::new();
+        };
+
+        let mut settled = Vec::new();
+        for seat in &self.hands {
+            let dealt = seat.hands.dealt_cards();
+            let mut hand = Hand::new();
+            for &card in dealt.iter().take(2) {
+                hand.add_card(card);
+            }
+            let cards = SideBetCards {
+                hand: &hand,
+                dealt,
+                dealer: &self.dealer,
+                upcard,
+                spin: self.rng.next_u32(),
+            };
+
+            for (id, &wager) in &seat.side_bets {
+                let bet = self.side_bets.get(id);
+                if bet.map_or(Resolution::AfterDealer, |b| b.resolution()) != resolution {
+                    continue;
+                }
+                let returned = match bet.map(|b| b.evaluate(wager, &cards)) {
+                    // No longer offered: the wager is handed back.
+                    None => wager as u64,
+                    Some(SideBetOutcome::Returned(returned)) => returned,
+                    Some(SideBetOutcome::Jackpot(prize)) => match self.jackpots.get_mut(id) {
+                        Some(pool) => pool.pay(wager, prize),
+                        None => wager as u64,
+                    },
+                };
+                settled.push((seat.player_id, wager as u64, returned));
+            }
+        }
+        settled
+    }
+
+    fn offers_insurance(&self) -> bool {
+        // With both dealer cards showing there is nothing to insure against.
+        self.rules.insurance_enabled
+            && !self.rules.dealer_cards_exposed()
+            && self.dealer_upcard().is_some_and(|c| c.rank == Rank::Ace)
+    }
+
+    /// Insures every box of the seat not already paid even money, `amount` on each, the way a
+    /// bet goes on each box.
+    fn insure(&mut self, player_id: Uuid, amount: u32) -> GameResult<()> {
+        if self.phase != Phase::Insurance {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+
+        let mut boxes = self.boxes(player_id)?;
+        boxes.retain(|&i| !self.hands[i].even_money);
+        if boxes.is_empty() || boxes.iter().any(|&i| self.hands[i].insurance_bet > 0) {
+            return Err(GameError::InvalidAction("already insured".into()));
+        }
+        let cap = boxes
+            .iter()
+            .map(|&i| self.hands[i].bet / 2)
+            .min()
+            .unwrap_or(0);
+        if amount == 0 || amount > cap {
+            return Err(GameError::InvalidAction(format!(
+                "insurance must be between 1 and {cap}"
+            )));
+        }
+
+        charge_player(
+            &mut self.players,
+            player_id,
+            amount.saturating_mul(boxes.len() as u32),
+        )?;
+        for i in boxes {
+            self.hands[i].insurance_bet = amount;
+        }
+        Ok(())
+    }
+
+    /// Takes even money on every box of the seat holding an uninsured natural.
+    fn take_even_money(&mut self, player_id: Uuid) -> GameResult<()> {
+        if self.phase != Phase::Insurance {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+
+        let mut boxes = self.boxes(player_id)?;
+        boxes.retain(|&i| {
+            let seat = &self.hands[i];
+            seat.hands.hands()[0].hand.is_blackjack() && seat.insurance_bet == 0 && !seat.even_money
+        });
+        if boxes.is_empty() {
+            return Err(GameError::InvalidAction(
+                "even money is not available".into(),
+            ));
+        }
+        for i in boxes {
+            self.hands[i].even_money = true;
+        }
+        Ok(())
+    }
+
+    /// Closes the insurance offer before anyone plays: even money is paid at once, and with a
+    /// hole card to check insurance is settled too.
+    fn settle_side_offers(&mut self) {
+        let mut settled: Vec<_> = self
+            .hands
+            .iter()
+            .filter(|h| h.even_money)
+            .map(|h| (h.player_id, h.bet as u64, h.bet as u64 * 2))
+            .collect();
+        if self.rules.dealer_has_hole_card() {
+            settled.extend(self.insurance_settlements());
+        }
+
+        for (player_id, wagered, returned) in settled {
+            self.record(player_id, wagered, returned);
+        }
+    }
+
+    /// Swaps the second cards of the player's two boxes. Each seat may switch once a round.
+    fn switch(&mut self, player_id: Uuid) -> GameResult<()> {
+        if self.phase != Phase::Switch {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+
+        let boxes = self.boxes(player_id)?;
+        let [left, right] = boxes[..] else {
+            return Err(GameError::PlayerNotFound(player_id));
+        };
+        if self.hands[left].switched {
+            return Err(GameError::InvalidAction("boxes already switched".into()));
+        }
+
+        let (head, tail) = self.hands.split_at_mut(right);
+        head[left].hands.switch_with(&mut tail[0].hands)?;
+        head[left].switched = true;
+        tail[0].switched = true;
+        Ok(())
+    }
+
+    /// US rules: with an Ace or ten up the dealer checks the hole card, and a natural ends the
+    /// round before anyone acts. Without a hole card there is nothing to check.
+    fn peek(&mut self) {
+        let upcard_checks = self
+            .dealer_upcard()
+            .is_some_and(|c| c.rank == Rank::Ace || c.pip_value() == 10);
+
+        if self.rules.dealer_has_hole_card() && upcard_checks && self.dealer.is_blackjack() {
+            self.phase = Phase::Payout;
+            return;
+        }
+
+        self.phase = Phase::PlayerTurns;
+        self.turn = 0;
+        self.skip_finished();
+    }
+
+    /// Until the round leaves `Dealing`/`Insurance` the dealer has not peeked, which is the
+    /// early surrender window, and every box of the seat goes at once. Once turns start it is
+    /// ordinary late surrender of the hand in play.
+    fn surrender(&mut self, player_id: Uuid) -> GameResult<()> {
+        if !matches!(self.phase, Phase::Dealing | Phase::Insurance) {
+            return self.play(player_id, Action::Surrender);
+        }
+
+        let upcard = self
+            .dealer_upcard()
+            .ok_or_else(|| GameError::InvalidAction("no upcard dealt".into()))?;
+        if !self.rules.surrender.allows_early(upcard) {
+            return Err(GameError::InvalidAction(
+                "early surrender not offered".into(),
+            ));
+        }
+
+        let boxes = self.boxes(player_id)?;
+        if !boxes
+            .iter()
+            .all(|&i| self.hands[i].hands.can_surrender(&self.rules))
+        {
+            return Err(GameError::InvalidAction(
+                "hand cannot be surrendered".into(),
+            ));
+        }
+        for i in boxes {
+            self.hands[i].hands.surrender(&self.rules)?;
+        }
+        Ok(())
+    }
+
+    fn play(&mut self, player_id: Uuid, action: Action) -> GameResult<()> {
+        if self.phase != Phase::PlayerTurns {
+            return Err(GameError::WrongPhase(self.phase.clone()));
+        }
+        if self.current_player() != Some(player_id) {
+            return Err(GameError::NotYourTurn);
+        }
+
+        let rules = &self.rules;
+        let seat = &mut self.hands[self.turn];
+        match action {
+            Action::Hit => {
+                if !seat.hands.can_hit(rules) {
+                    return Err(GameError::InvalidAction(
+                        "hand cannot take another card".into(),
+                    ));
+                }
+                let card = self.shoe.draw()?;
+                seat.hands.hit(rules, card)?;
+            }
+            Action::Stand => seat.hands.stand()?,
+            Action::Surrender => seat.hands.surrender(rules)?,
+            Action::Double => {
+                if !seat.hands.can_double(rules) {
+                    return Err(GameError::InvalidAction("hand cannot be doubled".into()));
+                }
+                let extra = seat.hands.double_cost(rules);
+                charge_player(&mut self.players, player_id, extra)?;
+                let card = self.shoe.draw()?;
+                seat.hands.double(rules, card)?;
+            }
+            Action::Buy => {
+                if !seat.hands.can_buy(rules) {
+                    return Err(GameError::InvalidAction("hand cannot buy a card".into()));
+                }
+                charge_player(&mut self.players, player_id, seat.hands.buy_cost())?;
+                let card = self.shoe.draw()?;
+                seat.hands.buy(rules, card)?;
+            }
+            Action::Split => {
+                if !seat.hands.can_split(rules) {
+                    return Err(GameError::InvalidAction("hand cannot be split".into()));
+                }
+                let extra = seat.hands.split_cost(rules);
+                charge_player(&mut self.players, player_id, extra)?;
+                let first = self.shoe.draw()?;
+                let second = self.shoe.draw()?;
+                seat.hands.split(rules, first, second)?;
+            }
+            other => return Err(GameError::InvalidAction(format!("{other:?} is not a play"))),
+        }
+
+        self.next_turn();
+        Ok(())
+    }
+
+    fn next_turn(&mut self) {
+        self.skip_finished();
+        if self.turn >= self.hands.len() {
+            self.phase = Phase::DealerTurn;
+        }
+    }
+
+    fn skip_finished(&mut self) {
+        while self
+            .hands
+            .get(self.turn)
+            .is_some_and(|h| h.hands.is_finished())
+        {
+            self.turn += 1;
+        }
+        if self.turn >= self.hands.len() {
+            self.phase = Phase::DealerTurn;
+        }
+    }
+
+    fn play_dealer(&mut self) -> GameResult<()> {
+        if !self.rules.dealer_has_hole_card() {
+            let card = self.shoe.draw()?;
+            self.dealer.add_card(card);
+        }
+
+        while self.dealer_should_hit() {
+            let card = self.shoe.draw()?;
+            self.dealer.add_card(card);
+        }
+
+        self.phase = Phase::Payout;
+        Ok(())
+    }
+
+    fn dealer_should_hit(&self) -> bool {
+        // No point drawing to a natural the players have already lost (or pushed) against.
+        if self.dealer.is_blackjack() {
+            return false;
+        }
+        // A banker's five-card trick beats any total, so there is nothing to draw for.
+        if self.rules.variant == Variant::Pontoon && self.dealer.cards().len() >= FIVE_CARD_TRICK {
+            return false;
+        }
+        let value = self.dealer.value();
+        value < 17 || (value == 17 && self.dealer.is_soft() && self.rules.dealer_hits_soft_17)
+    }
+
+    fn settle(&mut self) {
+        let mut settled = Vec::with_capacity(self.hands.len());
+        if !self.rules.dealer_has_hole_card() {
+            // Nothing could be checked up front, so insurance waits for the dealer's hand.
+            settled.extend(self.insurance_settlements());
+        }
+
+        settled.extend(self.settle_side_bets(Resolution::AfterDealer));
+
+        for seat in self.hands.iter().filter(|h| !h.even_money) {
+            settled.push((
+                seat.player_id,
+                seat.hands.total_bet(),
+                seat.hands.settle(&self.dealer, &self.rules),
+            ));
+        }
+
+        for (player_id, wagered, returned) in settled {
+            self.record(player_id, wagered, returned);
+        }
+        self.phase = Phase::RoundEnd;
+    }
+
+    fn insurance_settlements(&self) -> Vec<(Uuid, u64, u64)> {
+        self.hands
+            .iter()
+            .filter(|h| h.insurance_bet > 0)
+            .map(|h| {
+                let returned = calculate_insurance_payout(h.insurance_bet, &self.dealer);
+                (h.player_id, h.insurance_bet as u64, returned)
+            })
+            .collect()
+    }
+
+    /// Credits a settled wager to its player and adds it to the round's results.
+    fn record(&mut self, player_id: Uuid, wagered: u64, returned: u64) {
+        if let Some(player) = self.players.iter_mut().find(|p| p.id == player_id) {
+            player.credits = player
+                .credits
+                .saturating_add(returned.min(u32::MAX as u64) as u32);
+        }
+
+        match self.results.iter_mut().find(|r| r.player_id == player_id) {
+            Some(result) => {
+                result.wagered += wagered;
+                result.returned += returned;
+            }
+            None => self.results.push(RoundResult {
+                player_id,
+                wagered,
+                returned,
+            }),
+        }
+    }
+}
+
+fn build_shoe(rules: &Rules, seed: Option<u64>) -> ConfigResult<Shoe> {
+    let deck = rules.deck();
+    match rules.shuffle {
+        ShuffleMode::CutCard {
+            penetration_percent,
+        } => {
+            let total_cards = rules.num_decks as usize * deck.size();
+            let cut_position = (total_cards * penetration_percent as usize / 100).max(1);
+            Shoe::with_deck(deck, rules.num_decks, cut_position, seed)
+        }
+        ShuffleMode::Continuous { buffer } => {
+            Shoe::continuous(deck, rules.num_decks, buffer as usize, seed)
+        }
+    }
+}
+
+fn charge(player: &mut Player, amount: u32) -> GameResult<()> {
+    if player.credits < amount {
+        return Err(GameError::InsufficientCredits {
+            bet: amount,
+            credits: player.credits,
+        });
+    }
+    player.credits -= amount;
+    Ok(())
+}
+
+fn charge_player(players: &mut [Player], player_id: Uuid, amount: u32) -> GameResult<()> {
+    let player = players
+        .iter_mut()
+        .find(|p| p.id == player_id)
+        .ok_or(GameError::PlayerNotFound(player_id))?;
+    charge(player, amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::Suit;
+    use crate::core::payout::BlackjackPayout;
+    use crate::core::rules::{HoleCardRule, SurrenderRule};
+    use crate::core::side_bet::{
+        BLAZING_7S, BUST_IT, BUSTER_BLACKJACK, CARIBBEAN_21, PERFECT_PAIRS, ROYAL_MATCH, SUPER_7S,
+        TOP_3, TWENTY_ONE_PLUS_3,
+    };
+    use crate::error::ConfigError;
+
+    fn card(rank: Rank) -> Card {
+        Card::new(rank, Suit::Hearts)
+    }
+
+    // One seated player with a 10 credit bet down and the given cards on top of the shoe.
+    fn setup(rules: Rules, cards: &[Rank]) -> (Game, Uuid) {
+        let mut game = Game::new(rules, Some(7)).unwrap();
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, "p1".into(), 1000, false))
+            .unwrap();
+        game.apply(PlayerAction::new(id, Action::Bet { amount: 10 }))
+            .unwrap();
+        let cards: Vec<Card> = cards.iter().map(|&r| card(r)).collect();
+        game.shoe_mut().inject(&cards).unwrap();
+        (game, id)
+    }
+
+    fn side(id: &str, amount: u32) -> Action {
+        Action::BetSide {
+            id: id.into(),
+            amount,
+        }
+    }
+
+    fn act(game: &mut Game, id: Uuid, action: Action) {
+        game.apply(PlayerAction::new(id, action)).unwrap();
+    }
+
+    fn credits(game: &Game, id: Uuid) -> u32 {
+        game.player(id).unwrap().credits
+    }
+
+    #[test]
+    fn continuous_shuffler_never_runs_down() {
+        let rules = Rules {
+            shuffle: ShuffleMode::Continuous { buffer: 20 },
+            ..Rules::new()
+        };
+        let mut game = Game::new(rules, Some(3)).unwrap();
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, "p1".into(), 100_000, false))
+            .unwrap();
+
+        for _ in 0..500 {
+            act(&mut game, id, Action::Bet { amount: 10 });
+            while game.phase() != &Phase::RoundEnd {
+                match game.phase() {
+                    Phase::PlayerTurns => act(&mut game, id, Action::Stand),
+                    _ => game.advance().unwrap(),
+                }
+            }
+            game.advance().unwrap();
+            assert_eq!(game.shoe().remaining().len(), 312);
+        }
+    }
+
+    #[test]
+    fn chat_keeps_recent_lines() {
+        let (mut game, id) = setup(Rules::new(), &[]);
+        let say = |text: &str| PlayerAction::new(id, Action::Chat { text: text.into() });
+        assert!(game.apply(say("   ")).is_err());
+        assert!(game.apply(say(&"x".repeat(MAX_CHAT_LENGTH + 1))).is_err());
+
+        for i in 0..=CHAT_HISTORY {
+            game.apply(say(&format!("line {i}"))).unwrap();
+        }
+        let chat = game.snapshot().chat;
+        assert_eq!(chat.len(), CHAT_HISTORY);
+        assert_eq!(chat[0].text, "line 1");
+        assert_eq!(chat.last().unwrap().name, "p1");
+    }
+
+    #[test]
+    fn spectators_sit_back_down_to_bet() {
+        let mut game = Game::new(Rules::new(), Some(7)).unwrap();
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, "p1".into(), 1000, false))
+            .unwrap();
+        act(&mut game, id, Action::Spectate);
+        let bet = PlayerAction::new(id, Action::Bet { amount: 10 });
+        assert!(game.apply(bet.clone()).is_err());
+
+        for _ in 0..MAX_SEATS {
+            let other = Uuid::new_v4();
+            game.add_player(Player::new(other, "p".into(), 1000, false))
+                .unwrap();
+        }
+        assert!(matches!(
+            game.apply(PlayerAction::new(id, Action::Sit)),
+            Err(GameError::GameFull)
+        ));
+
+        let last = game.players()[MAX_SEATS].id;
+        act(&mut game, last, Action::Leave);
+        act(&mut game, id, Action::Sit);
+        game.apply(bet).unwrap();
+    }
+
+    #[test]
+    fn leaving_before_your_turn_keeps_the_table_moving() {
+        let (mut game, id) = setup(
+            Rules::new(),
+            &[Rank::Ten, Rank::Five, Rank::Nine, Rank::Nine, Rank::Eight],
+        );
+        let other = Uuid::new_v4();
+        game.add_player(Player::new(other, "p2".into(), 1000, false))
+            .unwrap();
+        act(&mut game, other, Action::Bet { amount: 10 });
+
+        // the second seat never gets dealt in
+        act(&mut game, other, Action::Leave);
+        assert_eq!(game.hands().len(), 1);
+
+        // the first leaves mid-deal, before their turn comes round
+        game.advance().unwrap();
+        act(&mut game, id, Action::Leave);
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::DealerTurn);
+        while game.phase() != &Phase::Betting {
+            game.advance().unwrap();
+        }
+        assert!(game.players().is_empty());
+    }
+
+    #[test]
+    fn full_round_player_wins() {
+        // player 10, dealer 9, player 9, dealer 8 (hole)
+        let (mut game, id) = setup(
+            Rules::new(),
+            &[Rank::Ten, Rank::Nine, Rank::Nine, Rank::Eight],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+        assert_eq!(game.current_player(), Some(id));
+
+        act(&mut game, id, Action::Stand);
+        assert_eq!(game.phase(), &Phase::DealerTurn);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::RoundEnd);
+        assert_eq!(credits(&game, id), 1010);
+
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::Betting);
+        assert!(game.hands().is_empty());
+    }
+
+    #[test]
+    fn peek_ends_round_on_dealer_blackjack() {
+        let (mut game, id) = setup(
+            Rules::new(),
+            &[Rank::Ten, Rank::Ace, Rank::Nine, Rank::King],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::Payout);
+
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 990);
+    }
+
+    #[test]
+    fn peek_without_blackjack_continues() {
+        let (mut game, _) = setup(
+            Rules::new(),
+            &[Rank::Ten, Rank::Ace, Rank::Nine, Rank::Five],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+    }
+
+    #[test]
+    fn enhc_dealer_draws_after_players() {
+        let rules = Rules {
+            hole_card: HoleCardRule::Enhc,
+            ..Rules::new()
+        };
+        // player 8, dealer A, player 8, then splits and the dealer's second card
+        let (mut game, id) = setup(
+            rules,
+            &[
+                Rank::Eight,
+                Rank::Ace,
+                Rank::Eight,
+                Rank::Two,
+                Rank::Three,
+                Rank::King,
+            ],
+        );
+        game.advance().unwrap();
+        assert_eq!(game.dealer().cards().len(), 1);
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+
+        act(&mut game, id, Action::Split);
+        act(&mut game, id, Action::Stand);
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        assert!(game.dealer().is_blackjack());
+        game.advance().unwrap();
+
+        // both split bets lost to the natural
+        assert_eq!(credits(&game, id), 980);
+    }
+
+    #[test]
+    fn obo_refunds_split_bet() {
+        let rules = Rules {
+            hole_card: HoleCardRule::Obo,
+            ..Rules::new()
+        };
+        let (mut game, id) = setup(
+            rules,
+            &[
+                Rank::Eight,
+                Rank::Ace,
+                Rank::Eight,
+                Rank::Two,
+                Rank::Three,
+                Rank::King,
+            ],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Split);
+        act(&mut game, id, Action::Stand);
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        game.advance().unwrap();
+
+        assert_eq!(credits(&game, id), 990);
+    }
+
+    #[test]
+    fn double_charges_and_pays() {
+        let (mut game, id) = setup(
+            Rules::new(),
+            &[Rank::Six, Rank::Ten, Rank::Five, Rank::Seven, Rank::Ten],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Double);
+        assert_eq!(credits(&game, id), 980);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1020);
+    }
+
+    #[test]
+    fn late_surrender_returns_half() {
+        let rules = Rules {
+            surrender: SurrenderRule::Late,
+            ..Rules::new()
+        };
+        let (mut game, id) = setup(rules, &[Rank::Ten, Rank::Ten, Rank::Six, Rank::Eight]);
+        game.advance().unwrap();
+
+        // not before the peek under late surrender
+        let early = game.apply(PlayerAction::new(id, Action::Surrender));
+        assert!(early.is_err());
+
+        game.advance().unwrap();
+        act(&mut game, id, Action::Surrender);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 995);
+    }
+
+    #[test]
+    fn early_surrender_beats_peek() {
+        let rules = Rules {
+            surrender: SurrenderRule::Early,
+            ..Rules::new()
+        };
+        let (mut game, id) = setup(rules, &[Rank::Ten, Rank::Ace, Rank::Six, Rank::King]);
+        game.advance().unwrap();
+        act(&mut game, id, Action::Surrender);
+
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::Payout);
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 995);
+    }
+
+    #[test]
+    fn early_except_ace_waits_for_peek() {
+        let rules = Rules {
+            surrender: SurrenderRule::EarlyExceptAce,
+            ..Rules::new()
+        };
+        let (mut game, id) = setup(rules, &[Rank::Ten, Rank::Ace, Rank::Six, Rank::Five]);
+        game.advance().unwrap();
+        assert!(
+            game.apply(PlayerAction::new(id, Action::Surrender))
+                .is_err()
+        );
+
+        game.advance().unwrap();
+        act(&mut game, id, Action::Surrender);
+        assert_eq!(game.phase(), &Phase::DealerTurn);
+    }
+
+    fn insurance_rules() -> Rules {
+        Rules {
+            insurance_enabled: true,
+            ..Rules::new()
+        }
+    }
+
+    #[test]
+    fn insurance_offered_under_ace() {
+        let (mut game, id) = setup(
+            insurance_rules(),
+            &[Rank::Ten, Rank::Ace, Rank::Nine, Rank::King],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::Insurance);
+
+        let over = game.apply(PlayerAction::new(id, Action::BetInsurance { amount: 6 }));
+        assert!(over.is_err());
+        act(&mut game, id, Action::BetInsurance { amount: 5 });
+        assert_eq!(credits(&game, id), 985);
+
+        // insurance is settled before the peek ends the round
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1000);
+        assert_eq!(game.phase(), &Phase::Payout);
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1000);
+    }
+
+    #[test]
+    fn insurance_lost_when_dealer_has_no_blackjack() {
+        let (mut game, id) = setup(
+            insurance_rules(),
+            &[Rank::Ten, Rank::Ace, Rank::Nine, Rank::Seven],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::BetInsurance { amount: 5 });
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+        assert_eq!(credits(&game, id), 985);
+    }
+
+    #[test]
+    fn no_insurance_under_ten() {
+        let (mut game, _) = setup(
+            insurance_rules(),
+            &[Rank::Ten, Rank::King, Rank::Nine, Rank::Seven],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+    }
+
+    #[test]
+    fn even_money_pays_natural_up_front() {
+        let (mut game, id) = setup(
+            insurance_rules(),
+            &[Rank::Ace, Rank::Ace, Rank::King, Rank::Queen],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::EvenMoney);
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1010);
+
+        // the dealer's natural does not take it back
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1010);
+        assert_eq!(game.results()[0].returned, 20);
+    }
+
+    #[test]
+    fn even_money_needs_natural() {
+        let (mut game, id) = setup(
+            insurance_rules(),
+            &[Rank::Ten, Rank::Ace, Rank::Nine, Rank::Seven],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert!(
+            game.apply(PlayerAction::new(id, Action::EvenMoney))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn side_bets_settle_on_the_deal() {
+        let rules = Rules {
+            perfect_pairs_enabled: true,
+            twenty_one_plus_3_enabled: true,
+            ..Rules::new()
+        };
+        // hearts throughout: 8-8 perfect pair, 8-8-8 suited trips with the dealer's upcard
+        let (mut game, id) = setup(rules, &[Rank::Eight, Rank::Eight, Rank::Eight, Rank::Ten]);
+        act(&mut game, id, side(PERFECT_PAIRS, 5));
+        act(&mut game, id, side(TWENTY_ONE_PLUS_3, 5));
+        assert_eq!(credits(&game, id), 980);
+
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 980 + 130 + 505);
+    }
+
+    #[test]
+    fn side_bet_rules_enforced() {
+        let (mut game, id) = setup(Rules::new(), &[]);
+        let disabled = game.apply(PlayerAction::new(id, side(ROYAL_MATCH, 5)));
+        assert!(disabled.is_err());
+
+        let rules = Rules {
+            royal_match_enabled: true,
+            ..Rules::new()
+        };
+        let (mut game, id) = setup(rules, &[]);
+        let over = game.apply(PlayerAction::new(id, side(ROYAL_MATCH, 11)));
+        assert!(over.is_err());
+        act(&mut game, id, side(ROYAL_MATCH, 10));
+        let again = game.apply(PlayerAction::new(id, side(ROYAL_MATCH, 1)));
+        assert!(again.is_err());
+    }
+
+    #[test]
+    fn bust_it_settles_after_dealer() {
+        let rules = Rules {
+            bust_it_enabled: true,
+            ..Rules::new()
+        };
+        // player 10-8 stands, dealer 10-6 draws a King: three-card bust
+        let (mut game, id) = setup(
+            rules,
+            &[Rank::Ten, Rank::Ten, Rank::Eight, Rank::Six, Rank::King],
+        );
+        act(&mut game, id, side(BUST_IT, 5));
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 985);
+        game.advance().unwrap();
+
+        assert_eq!(credits(&game, id), 985 + 10 + 20);
+    }
+
+    #[test]
+    fn super_7s_counts_third_card() {
+        let rules = Rules {
+            super_7s_enabled: true,
+            ..Rules::new()
+        };
+        // hearts throughout: 7-7 then a third 7 on the hit
+        let (mut game, id) = setup(
+            rules,
+            &[
+                Rank::Seven,
+                Rank::Ten,
+                Rank::Seven,
+                Rank::Seven,
+                Rank::Seven,
+            ],
+        );
+        act(&mut game, id, side(SUPER_7S, 1));
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Hit);
+        game.advance().unwrap();
+        game.advance().unwrap();
+
+        assert_eq!(game.results()[0].returned, 5001 + 20);
+    }
+
+    #[test]
+    fn buster_settles_after_dealer_and_top_3_on_deal() {
+        let rules = Rules {
+            buster_blackjack_enabled: true,
+            top_3_enabled: true,
+            ..Rules::new()
+        };
+        // player 9-9 with a 9 up (trips), dealer 9-5 draws a King
+        let (mut game, id) = setup(
+            rules,
+            &[Rank::Nine, Rank::Nine, Rank::Nine, Rank::Five, Rank::King],
+        );
+        act(&mut game, id, side(BUSTER_BLACKJACK, 1));
+        act(&mut game, id, side(TOP_3, 1));
+        game.advance().unwrap();
+        // Top 3 suited trips resolves with the deal, Buster is still riding
+        assert_eq!(credits(&game, id), 988 + 271);
+
+        game.advance().unwrap();
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 988 + 271 + 3 + 20);
+    }
+
+    #[test]
+    fn progressive_wager_feeds_meter_and_pays_from_it() {
+        let rules = Rules {
+            blazing_7s_enabled: true,
+            ..Rules::new()
+        };
+        // hearts throughout: three suited sevens with the dealer's upcard
+        let (mut game, id) = setup(rules, &[Rank::Seven, Rank::Seven, Rank::Seven, Rank::Ten]);
+        act(&mut game, id, side(BLAZING_7S, 10));
+        assert_eq!(game.jackpots().get(BLAZING_7S).unwrap().value, 10_001);
+
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 980 + 10 + 10_001);
+        assert_eq!(game.jackpots().get(BLAZING_7S).unwrap().value, 10_000);
+    }
+
+    #[test]
+    fn snapshot_hides_hole_card_until_dealer_turn() {
+        let rules = Rules {
+            caribbean_21_enabled: true,
+            ..Rules::new()
+        };
+        let (mut game, id) = setup(rules, &[Rank::Ten, Rank::Nine, Rank::Nine, Rank::Eight]);
+        act(&mut game, id, side(CARIBBEAN_21, 10));
+        game.advance().unwrap();
+        game.advance().unwrap();
+
+        let snapshot = game.snapshot();
+        assert_eq!(snapshot.dealer_cards, vec![card(Rank::Nine)]);
+        assert_eq!(snapshot.dealer_hidden_cards, 1);
+        assert_eq!(snapshot.current_player, Some(id));
+        assert_eq!(snapshot.jackpots.get(CARIBBEAN_21).unwrap().value, 10_001);
+
+        act(&mut game, id, Action::Stand);
+        assert_eq!(game.snapshot().dealer_cards.len(), 2);
+    }
+
+    #[test]
+    fn fractional_bets_rejected_when_configured() {
+        let rules = Rules {
+            payout_rounding: RoundingPolicy::RejectFractional,
+            ..Rules::new()
+        };
+        let mut game = Game::new(rules, Some(7)).unwrap();
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, "p1".into(), 1000, false))
+            .unwrap();
+
+        let odd = game.apply(PlayerAction::new(id, Action::Bet { amount: 15 }));
+        assert!(matches!(
+            odd,
+            Err(GameError::BetNotDivisible { bet: 15, unit: 2 })
+        ));
+        act(&mut game, id, Action::Bet { amount: 16 });
+
+        // 6:5 pays in fives, and a surrender then needs an even bet too
+        let rules = Rules {
+            payout_rounding: RoundingPolicy::RejectFractional,
+            blackjack_payout: BlackjackPayout::Vegas,
+            surrender: SurrenderRule::Late,
+            ..Rules::new()
+        };
+        let mut game = Game::new(rules, Some(7)).unwrap();
+        game.add_player(Player::new(id, "p1".into(), 1000, false))
+            .unwrap();
+        let odd = game.apply(PlayerAction::new(id, Action::Bet { amount: 15 }));
+        assert!(matches!(
+            odd,
+            Err(GameError::BetNotDivisible { bet: 15, unit: 10 })
+        ));
+        act(&mut game, id, Action::Bet { amount: 20 });
+    }
+
+    #[test]
+    fn invalid_custom_ratio_refused_at_creation() {
+        let rules = Rules {
+            blackjack_payout: BlackjackPayout::Custom {
+                numerator: 3,
+                denominator: 0,
+            },
+            ..Rules::new()
+        };
+        assert!(Game::new(rules, None).is_err());
+    }
+
+    #[test]
+    fn invalid_rules_refused_at_creation() {
+        let rules = Rules {
+            num_decks: 0,
+            min_bet: 0,
+            ..Rules::new()
+        };
+        let Err(ConfigError::InvalidRules(errors)) = Game::new(rules, None) else {
+            panic!("rules should be rejected");
+        };
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn host_changes_rules_between_rounds() {
+        let mut game = Game::new(Rules::new(), Some(7)).unwrap();
+        let host = Uuid::new_v4();
+        let guest = Uuid::new_v4();
+        let mut player = Player::new(host, "host".into(), 1000, false);
+        player.is_host = true;
+        game.add_player(player).unwrap();
+        game.add_player(Player::new(guest, "guest".into(), 1000, false))
+            .unwrap();
+
+        let eight_decks = Rules {
+            num_decks: 8,
+            ..Rules::new()
+        };
+        assert!(game.change_rules(guest, eight_decks.clone()).is_err());
+
+        let broken = Rules {
+            min_bet: 600,
+            ..Rules::new()
+        };
+        assert!(matches!(
+            game.change_rules(host, broken),
+            Err(GameError::Config(ConfigError::InvalidRules(_)))
+        ));
+
+        game.change_rules(host, eight_decks).unwrap();
+        assert_eq!(game.rules().num_decks, 8);
+
+        act(&mut game, guest, Action::Bet { amount: 10 });
+        assert!(matches!(
+            game.change_rules(host, Rules::new()),
+            Err(GameError::WrongPhase(Phase::Betting))
+        ));
+    }
+
+    #[test]
+    fn spanish_21_rescue_after_double() {
+        // player 6-5 doubles into 13, dealer 10-7
+        let (mut game, id) = setup(
+            Rules::spanish_21(),
+            &[Rank::Six, Rank::King, Rank::Five, Rank::Seven, Rank::Two],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Double);
+        assert_eq!(game.current_player(), Some(id));
+        assert!(game.apply(PlayerAction::new(id, Action::Hit)).is_err());
+
+        act(&mut game, id, Action::Surrender);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 990);
+    }
+
+    #[test]
+    fn switch_swaps_second_cards_between_boxes() {
+        // boxes 10-5 and 6-Ace switch into 10-Ace and 6-5, dealer 9-8
+        let (mut game, id) = setup(
+            Rules::blackjack_switch(),
+            &[
+                Rank::Ten,
+                Rank::Six,
+                Rank::Nine,
+                Rank::Five,
+                Rank::Ace,
+                Rank::Eight,
+                Rank::Four,
+            ],
+        );
+        assert_eq!(credits(&game, id), 980);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::Switch);
+        assert!(game.apply(PlayerAction::new(id, Action::Hit)).is_err());
+
+        act(&mut game, id, Action::Switch);
+        assert!(game.apply(PlayerAction::new(id, Action::Switch)).is_err());
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+
+        // The 21 needs no decision; the 11 doubles into 15 and loses to 17.
+        assert_eq!(game.hands()[0].hands.hands()[0].hand.value(), 21);
+        act(&mut game, id, Action::Double);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        // the 21 wins even money: 980 - 10 (double) + 20
+        assert_eq!(credits(&game, id), 990);
+    }
+
+    #[test]
+    fn switched_21_pushes_a_dealer_21() {
+        // boxes 10-5 and 6-Ace switch into 10-Ace and 6-5, dealer 9-5 draws a 7
+        let (mut game, id) = setup(
+            Rules::blackjack_switch(),
+            &[
+                Rank::Ten,
+                Rank::Six,
+                Rank::Nine,
+                Rank::Five,
+                Rank::Ace,
+                Rank::Five,
+                Rank::Seven,
+            ],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Switch);
+        game.advance().unwrap();
+
+        let switched = &game.hands()[0].hands.hands()[0].hand;
+        assert_eq!(switched.value(), 21);
+        assert!(!switched.is_blackjack());
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.dealer().value(), 21);
+        // the 21 takes back its 10, the 11 loses
+        assert_eq!(credits(&game, id), 990);
+    }
+
+    fn switch_insurance_rules() -> Rules {
+        Rules {
+            insurance_enabled: true,
+            ..Rules::blackjack_switch()
+        }
+    }
+
+    #[test]
+    fn switch_insures_both_boxes() {
+        // boxes 10-7 and 9-8, dealer A-K
+        let (mut game, id) = setup(
+            switch_insurance_rules(),
+            &[
+                Rank::Ten,
+                Rank::Nine,
+                Rank::Ace,
+                Rank::Seven,
+                Rank::Eight,
+                Rank::King,
+            ],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::Insurance);
+
+        act(&mut game, id, Action::BetInsurance { amount: 5 });
+        assert_eq!(credits(&game, id), 970);
+        assert!(game.hands().iter().all(|h| h.insurance_bet == 5));
+
+        // each box's insurance pays 2:1 against the natural, covering both lost bets
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1000);
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 1000);
+    }
+
+    #[test]
+    fn switch_second_box_takes_even_money() {
+        // boxes 10-7 and A-K, dealer A-9
+        let (mut game, id) = setup(
+            switch_insurance_rules(),
+            &[
+                Rank::Ten,
+                Rank::Ace,
+                Rank::Ace,
+                Rank::Seven,
+                Rank::King,
+                Rank::Nine,
+            ],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        game.advance().unwrap();
+
+        act(&mut game, id, Action::EvenMoney);
+        assert!(!game.hands()[0].even_money && game.hands()[1].even_money);
+        assert!(
+            game.apply(PlayerAction::new(id, Action::EvenMoney))
+                .is_err()
+        );
+
+        // insurance still goes on the other box
+        act(&mut game, id, Action::BetInsurance { amount: 5 });
+        assert_eq!(credits(&game, id), 975);
+        assert_eq!(game.hands()[0].insurance_bet, 5);
+        assert_eq!(game.hands()[1].insurance_bet, 0);
+
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+        assert_eq!(credits(&game, id), 995);
+    }
+
+    #[test]
+    fn switch_early_surrender_gives_up_both_boxes() {
+        let rules = Rules {
+            surrender: SurrenderRule::Early,
+            ..Rules::blackjack_switch()
+        };
+        // boxes 10-6 and 10-6, dealer 10-7
+        let (mut game, id) = setup(
+            rules,
+            &[
+                Rank::Ten,
+                Rank::Ten,
+                Rank::Ten,
+                Rank::Six,
+                Rank::Six,
+                Rank::Seven,
+            ],
+        );
+        game.advance().unwrap();
+        act(&mut game, id, Action::Surrender);
+        assert!(game.hands().iter().all(|h| h.hands.is_finished()));
+
+        while game.phase() != &Phase::RoundEnd {
+            game.advance().unwrap();
+        }
+        assert_eq!(credits(&game, id), 990);
+    }
+
+    #[test]
+    fn switch_dealer_22_pushes() {
+        // boxes 10-8 and 10-7 stand, dealer 10-6 draws a 6
+        let (mut game, id) = setup(
+            Rules::blackjack_switch(),
+            &[
+                Rank::Ten,
+                Rank::Ten,
+                Rank::Ten,
+                Rank::Eight,
+                Rank::Seven,
+                Rank::Six,
+                Rank::Six,
+            ],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Stand);
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(game.dealer().value(), 22);
+        assert_eq!(credits(&game, id), 1000);
+    }
+
+    #[test]
+    fn free_bet_split_costs_nothing() {
+        // 8-8 splits free into 8-10 and 8-10, dealer 10-7
+        let (mut game, id) = setup(
+            Rules::free_bet(),
+            &[
+                Rank::Eight,
+                Rank::Ten,
+                Rank::Eight,
+                Rank::Seven,
+                Rank::Ten,
+                Rank::Ten,
+            ],
+        );
+        game.advance().unwrap();
+        game.advance().unwrap();
+        act(&mut game, id, Action::Split);
+        assert_eq!(credits(&game, id), 990);
+        assert_eq!(game.hands()[0].free_chips(), 10);
+
+        act(&mut game, id, Action::Stand);
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        // both hands win: 20 back on the own chips, 10 winnings on the free ones
+        assert_eq!(credits(&game, id), 1020);
+    }
+
+    #[test]
+    fn double_exposure_shows_both_dealer_cards() {
+        let mut rules = Rules::double_exposure();
+        rules.insurance_enabled = true;
+        let (mut game, _) = setup(rules, &[Rank::Ten, Rank::Ace, Rank::Nine, Rank::Six]);
+        game.advance().unwrap();
+        let snapshot = game.snapshot();
+        assert_eq!(snapshot.dealer_cards.len(), 2);
+        assert_eq!(snapshot.dealer_hidden_cards, 0);
+
+        // no insurance against an exposed Ace
+        game.advance().unwrap();
+        assert_eq!(game.phase(), &Phase::PlayerTurns);
+    }
+
+    #[test]
+    fn pontoon_hides_banker_and_buys() {
+        // player 5-6 buys a 4 then sticks on 15, banker 10-8 wins
+        let (mut game, id) = setup(
+            Rules::pontoon(),
+            &[Rank::Five, Rank::Ten, Rank::Six, Rank::Eight, Rank::Four],
+        );
+        game.advance().unwrap();
+        assert!(game.snapshot().dealer_cards.is_empty());
+        assert_eq!(game.snapshot().dealer_hidden_cards, 2);
+        game.advance().unwrap();
+
+        assert!(game.apply(PlayerAction::new(id, Action::Double)).is_err());
+        act(&mut game, id, Action::Buy);
+        assert_eq!(credits(&game, id), 980);
+        act(&mut game, id, Action::Stand);
+        game.advance().unwrap();
+        game.advance().unwrap();
+        assert_eq!(credits(&game, id), 980);
+    }
+
+    #[test]
+    fn out_of_turn_rejected() {
+        let (mut game, _) = setup(
+            Rules::new(),
+            &[Rank::Ten, Rank::Nine, Rank::Nine, Rank::Eight],
+        );
+        let other = Uuid::new_v4();
+        game.add_player(Player::new(other, "p2".into(), 1000, false))
+            .unwrap();
+        game.advance().unwrap();
+        game.advance().unwrap();
+
+        let result = game.apply(PlayerAction::new(other, Action::Hit));
+        assert!(matches!(result, Err(GameError::NotYourTurn)));
+    }
+
+    #[test]
+    fn bet_limits_enforced() {
+        let mut game = Game::new(Rules::new(), Some(7)).unwrap();
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, "p1".into(), 1000, false))
+            .unwrap();
+
+        let low = game.apply(PlayerAction::new(id, Action::Bet { amount: 1 }));
+        assert!(matches!(low, Err(GameError::BetTooLow { .. })));
+        let high = game.apply(PlayerAction::new(id, Action::Bet { amount: 10_000 }));
+        assert!(matches!(high, Err(GameError::BetTooHigh { .. })));
+    }
+}
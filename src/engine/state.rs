@@ -1 +1,20 @@
+use crate::core::card::Card;
+use crate::core::progressive::Jackpots;
+use crate::types::chat::ChatMessage;
+use crate::types::phase::Phase;
+use crate::types::player::{Player, PlayerHand};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+/// What every seat and spectator is allowed to see of the table.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub phase: Phase,
+    pub players: Vec<Player>,
+    pub hands: Vec<PlayerHand>,
+    pub dealer_cards: Vec<Card>, // hole card left out until the dealer turns it over
+    pub dealer_hidden_cards: usize,
+    pub current_player: Option<Uuid>,
+    pub jackpots: Jackpots,
+    pub chat: Vec<ChatMessage>, // most recent last
+}
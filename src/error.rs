@@ -1,4 +1,6 @@
+use crate::types::phase::Phase;
 use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -11,6 +13,33 @@ pub enum ConfigError {
     #[error("Invalid bet limits: must be between {min} and {max}")]
     InvalidBetLimits { min: u32, max: u32 },
 
+    #[error("Invalid payout ratio: {numerator}:{denominator}")]
+    InvalidPayoutRatio { numerator: u32, denominator: u32 },
+
+    #[error("Invalid chip size: {0}, must be even and non-zero")]
+    InvalidChipSize(u32),
+
+    #[error("Conflicting rules: {0}")]
+    ConflictingRules(String),
+
+    #[error("Side bet {0} is enabled without a paytable")]
+    MissingPaytable(String),
+
+    #[error("Invalid percentage: {0}, must be at most 100")]
+    InvalidPercentage(u8),
+
+    #[error("Invalid rules: {}", join_errors(.0))]
+    InvalidRules(Vec<ConfigError>),
+
+    #[error("Unknown rule preset: {0}")]
+    UnknownPreset(String),
+
+    #[error("Unknown counting system: {0}")]
+    UnknownCountSystem(String),
+
+    #[error("Unknown bot strategy: {0}")]
+    UnknownBotStrategy(String),
+
     #[error("Invalid port: {0}")]
     InvalidPort(u16),
 
@@ -23,17 +52,38 @@ pub enum GameError {
     #[error("Shoe needs reshuffling")]
     ShoeNeedsReshuffling,
 
+    #[error("Shoe is empty")]
+    ShoeEmpty,
+
     #[error("Bet too low: {bet} < min {min}")]
     BetTooLow { bet: u32, min: u32 },
 
     #[error("Bet too high: {bet} > max {max}")]
     BetTooHigh { bet: u32, max: u32 },
 
+    #[error("Bet {bet} cannot be paid exactly, must be a multiple of {unit}")]
+    BetNotDivisible { bet: u32, unit: u32 },
+
     #[error("Insufficient credits: need {bet}, have {credits}")]
     InsufficientCredits { bet: u32, credits: u32 },
 
     #[error("Game is full")]
     GameFull,
+
+    #[error("Invalid action: {0}")]
+    InvalidAction(String),
+
+    #[error("Action not allowed during {0:?}")]
+    WrongPhase(Phase),
+
+    #[error("Not your turn")]
+    NotYourTurn,
+
+    #[error("Player not found: {0}")]
+    PlayerNotFound(Uuid),
+
+    #[error(transparent)]
+    Config(#[from] ConfigError),
 }
 
 #[derive(Error, Debug)]
@@ -69,6 +119,14 @@ pub enum PersistenceError {
     SessionNotFoundError(String),
 }
 
+fn join_errors(errors: &[ConfigError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
 pub type GameResult<T> = std::result::Result<T, GameError>;
 pub type NetworkResult<T> = std::result::Result<T, NetworkError>;
@@ -1 +1,12 @@
-
+pub mod app;
+pub mod config;
+pub mod core;
+pub mod engine;
+pub mod error;
+pub mod logging;
+pub mod net;
+pub mod persist;
+pub mod sim;
+pub mod strategy;
+pub mod tui;
+pub mod types;
@@ -1,12 +1,17 @@
-mod app;
-mod config;
-mod core;
-mod engine;
-mod error;
-mod logging;
-mod net;
-mod persist;
-mod tui;
-mod types;
+use blackjack::sim;
 
-fn main() {}
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("sim") {
+        let config = sim::parse_args(&args[1..])?;
+        if config.compare.is_empty() {
+            println!("{}", sim::run(&config)?);
+        } else {
+            println!("{}", sim::compare_shuffles(&config, &config.compare)?);
+        }
+    } else if args.first().map(String::as_str) == Some("tournament") {
+        let config = sim::tournament::parse_args(&args[1..])?;
+        println!("{}", sim::tournament::run(&config)?);
+    }
+    Ok(())
+}
@@ -1,2 +1,2 @@
-mod session;
+pub mod session;
 mod stats;
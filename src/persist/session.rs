@@ -1 +1,104 @@
+use crate::core::progressive::Jackpots;
+use crate::core::rules::Rules;
+use crate::engine::game::Game;
+use crate::error::{PersistenceError, PersistenceResult};
+use crate::types::player::Player;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
 
+/// Everything a host needs to pick a session back up between rounds. Hands in play are not
+/// saved; the table resumes at betting.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionSave {
+    pub rules: Rules,
+    pub players: Vec<Player>,
+    pub jackpots: Jackpots,
+}
+
+impl SessionSave {
+    pub fn capture(game: &Game) -> Self {
+        Self {
+            rules: game.rules().clone(),
+            players: game.players().to_vec(),
+            jackpots: game.jackpots().clone(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> PersistenceResult<()> {
+        let text = toml::to_string(self).map_err(std::io::Error::other)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> PersistenceResult<Self> {
+        let text = fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                PersistenceError::SessionNotFoundError(path.display().to_string())
+            }
+            _ => PersistenceError::LoadError(e.to_string()),
+        })?;
+        toml::from_str(&text).map_err(|e| PersistenceError::CorruptedSaveError(e.to_string()))
+    }
+
+    pub fn restore(self, seed: Option<u64>) -> PersistenceResult<Game> {
+        let corrupted =
+            |e: &dyn std::fmt::Display| PersistenceError::CorruptedSaveError(e.to_string());
+        let mut game = Game::new(self.rules, seed).map_err(|e| corrupted(&e))?;
+        for player in self.players {
+            game.add_player(player).map_err(|e| corrupted(&e))?;
+        }
+        game.set_jackpots(self.jackpots);
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::side_bet::CARIBBEAN_21;
+    use crate::types::action::{Action, PlayerAction};
+    use uuid::Uuid;
+
+    #[test]
+    fn jackpot_meter_survives_save_and_load() {
+        let rules = Rules {
+            caribbean_21_enabled: true,
+            ..Rules::new()
+        };
+        let mut game = Game::new(rules, Some(3)).unwrap();
+        let id = Uuid::new_v4();
+        game.add_player(Player::new(id, "p1".into(), 1000, false))
+            .unwrap();
+        game.apply(PlayerAction::new(id, Action::Bet { amount: 100 }))
+            .unwrap();
+        let bet = Action::BetSide {
+            id: CARIBBEAN_21.into(),
+            amount: 50,
+        };
+        game.apply(PlayerAction::new(id, bet)).unwrap();
+        assert_eq!(game.jackpots().get(CARIBBEAN_21).unwrap().value, 10_005);
+
+        let path = std::env::temp_dir()
+            .join(format!("blackjack-{}", Uuid::new_v4()))
+            .join("session.toml");
+        SessionSave::capture(&game).save(&path).unwrap();
+        let restored = SessionSave::load(&path).unwrap().restore(None).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(restored.jackpots(), game.jackpots());
+        assert_eq!(restored.player(id).unwrap().credits, 850);
+    }
+
+    #[test]
+    fn missing_session_reported() {
+        let path = std::env::temp_dir().join(format!("blackjack-{}.toml", Uuid::new_v4()));
+        assert!(matches!(
+            SessionSave::load(&path),
+            Err(PersistenceError::SessionNotFoundError(_))
+        ));
+    }
+}
@@ -0,0 +1,392 @@
+//! Headless Monte Carlo runs of the real table. Every round goes through [`Game`], so hands are
+//! settled by exactly the `core::payout` code players see, and the numbers here validate it.
+
+pub mod tournament;
+
+use crate::config::TableConfig;
+use crate::core::rules::{Rules, ShuffleMode};
+use crate::engine::bot::{BotKind, Bots};
+use crate::engine::game::Game;
+use crate::error::{ConfigError, ConfigResult, GameResult};
+use crate::strategy::chart::StrategyChart;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    pub rules: Rules,
+    pub strategy: BotKind,
+    pub chart: Arc<StrategyChart>, // generated for `rules`, shared by the workers
+    pub rounds: u64,
+    pub threads: usize,
+    pub seed: u64,
+    pub bankroll: u32,             // in base bets, for the risk of ruin estimate
+    pub compare: Vec<ShuffleMode>, // run once under each instead of under `rules.shuffle`
+}
+
+/// Per-round results in units of the table minimum, the base bet every bot sizes its own
+/// bets from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimReport {
+    pub rounds: u64,
+    pub sum: f64,
+    pub sum_squares: f64,
+    pub elapsed: Duration,
+    pub bankroll: u32,
+}
+
+impl SimReport {
+    pub fn ev(&self) -> f64 {
+        self.sum / self.rounds.max(1) as f64
+    }
+
+    pub fn variance(&self) -> f64 {
+        let n = self.rounds.max(1) as f64;
+        (self.sum_squares / n - self.ev().powi(2)).max(0.0)
+    }
+
+    pub fn standard_error(&self) -> f64 {
+        (self.variance() / self.rounds.max(1) as f64).sqrt()
+    }
+
+    /// Chance of losing the whole bankroll playing forever at this EV and variance, from the
+    /// diffusion approximation `exp(-2 * ev * bankroll / variance)`.
+    pub fn risk_of_ruin(&self) -> f64 {
+        let (ev, variance) = (self.ev(), self.variance());
+        if ev <= 0.0 || variance == 0.0 {
+            return 1.0;
+        }
+        (-2.0 * ev * self.bankroll as f64 / variance).exp().min(1.0)
+    }
+
+    pub fn hands_per_second(&self) -> f64 {
+        self.rounds as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    fn merge(&mut self, other: SimReport) {
+        self.rounds += other.rounds;
+        self.sum += other.sum;
+        self.sum_squares += other.sum_squares;
+    }
+}
+
+impl fmt::Display for SimReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rounds        {}", self.rounds)?;
+        writeln!(f, "ev            {:+.4}%", self.ev() * 100.0)?;
+        writeln!(f, "variance      {:.4}", self.variance())?;
+        writeln!(f, "std error     {:.4}%", self.standard_error() * 100.0)?;
+        writeln!(
+            f,
+            "risk of ruin  {:.2}% ({} bets)",
+            self.risk_of_ruin() * 100.0,
+            self.bankroll
+        )?;
+        write!(f, "hands/sec     {:.0}", self.hands_per_second())
+    }
+}
+
+/// Plays `config.rounds` rounds split across the worker threads. Worker `i` shuffles with
+/// `seed + i`, so a run is repeatable for a given seed and thread count.
+pub fn run(config: &SimConfig) -> GameResult<SimReport> {
+    let started = Instant::now();
+    let threads = config.threads.max(1);
+    let results = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|i| {
+                let rounds = config.rounds / threads as u64
+                    + u64::from((i as u64) < config.rounds % threads as u64);
+                let seed = config.seed.wrapping_add(i as u64);
+                scope.spawn(move || play(config, rounds, seed))
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().expect("simulation worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut report = SimReport {
+        bankroll: config.bankroll,
+        ..SimReport::default()
+    };
+    for result in results {
+        report.merge(result?);
+    }
+    report.elapsed = started.elapsed();
+    Ok(report)
+}
+
+/// The same run under each shuffle mode in turn, everything else held fixed.
+pub fn compare_shuffles(
+    config: &SimConfig,
+    modes: &[ShuffleMode],
+) -> GameResult<ShuffleComparison> {
+    let mut runs = Vec::with_capacity(modes.len());
+    for &mode in modes {
+        let mut config = config.clone();
+        config.rules.shuffle = mode;
+        config.rules.validate()?;
+        runs.push((mode, run(&config)?));
+    }
+    Ok(ShuffleComparison { runs })
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShuffleComparison {
+    pub runs: Vec<(ShuffleMode, SimReport)>,
+}
+
+impl fmt::Display for ShuffleComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<10} {:>10} {:>10} {:>10} {:>10}",
+            "shuffle", "rounds", "ev", "std error", "variance"
+        )?;
+        for (mode, report) in &self.runs {
+            write!(
+                f,
+                "\n{:<10} {:>10} {:>+9.4}% {:>9.4}% {:>10.4}",
+                mode.to_string(),
+                report.rounds,
+                report.ev() * 100.0,
+                report.standard_error() * 100.0,
+                report.variance()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn play(config: &SimConfig, rounds: u64, seed: u64) -> GameResult<SimReport> {
+    let mut game = Game::new(config.rules.clone(), Some(seed))?;
+    let mut bots = Bots::new();
+    let kind = config.strategy;
+    let strategy = kind.build(&config.rules, &config.chart, seed);
+    // Deep enough pockets that no run of losses ends the session.
+    let id = bots.seat(&mut game, kind.name(), u32::MAX / 2, strategy)?;
+    let unit = config.rules.min_bet.max(1) as f64;
+
+    let mut report = SimReport::default();
+    for _ in 0..rounds {
+        bots.play_round(&mut game)?;
+        let net = game
+            .results()
+            .iter()
+            .filter(|r| r.player_id == id)
+            .map(|r| r.returned as f64 - r.wagered as f64)
+            .sum::<f64>()
+            / unit;
+        report.rounds += 1;
+        report.sum += net;
+        report.sum_squares += net * net;
+    }
+    Ok(report)
+}
+
+/// `blackjack sim` arguments:
+/// `--rounds N --threads N --seed N --bet N --bankroll N --strategy NAME --preset NAME
+/// --config PATH --shuffle MODE --compare MODE,MODE,...`, where a mode is `cut:PERCENT` or
+/// `csm:BUFFER`. `--bet` plays the table at that minimum, the flat bettors' stake and where
+/// the progressions start.
+pub fn parse_args(args: &[String]) -> ConfigResult<SimConfig> {
+    let mut rounds = 1_000_000;
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut seed = 1;
+    let mut bet = None;
+    let mut bankroll = 100;
+    let mut strategy = BotKind::Basic;
+    let mut rules = Rules::new();
+    let mut shuffle = None;
+    let mut compare = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| ConfigError::Other(format!("{flag} needs a value")))?;
+        match flag.as_str() {
+            "--rounds" => rounds = number(flag, value)?,
+            "--threads" => threads = number(flag, value)?,
+            "--seed" => seed = number(flag, value)?,
+            "--bet" => bet = Some(number(flag, value)?),
+            "--bankroll" => bankroll = number(flag, value)?,
+            "--strategy" => strategy = value.parse()?,
+            "--preset" => rules = Rules::preset(value)?,
+            "--config" => rules = load_rules(value)?,
+            "--shuffle" => shuffle = Some(value.parse()?),
+            "--compare" => {
+                compare = value
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<ConfigResult<_>>()?
+            }
+            other => return Err(ConfigError::Other(format!("unknown option: {other}"))),
+        }
+    }
+
+    if let Some(shuffle) = shuffle {
+        rules.shuffle = shuffle;
+    }
+    if let Some(bet) = bet {
+        if bet < rules.min_bet || bet > rules.max_bet {
+            return Err(ConfigError::InvalidBetLimits {
+                min: rules.min_bet,
+                max: rules.max_bet,
+            });
+        }
+        rules.min_bet = bet;
+    }
+    rules.validate()?;
+    for &mode in &compare {
+        Rules {
+            shuffle: mode,
+            ..rules.clone()
+        }
+        .validate()?;
+    }
+    Ok(SimConfig {
+        strategy,
+        chart: Arc::new(StrategyChart::generate(&rules)),
+        rules,
+        rounds,
+        threads,
+        seed,
+        bankroll,
+        compare,
+    })
+}
+
+/// Rules from a table config file, as `--config` takes them.
+fn load_rules(path: &str) -> ConfigResult<Rules> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| ConfigError::Other(format!("{path}: {e}")))?;
+    TableConfig::from_toml(&text)?.rules()
+}
+
+fn number<T: FromStr>(flag: &str, value: &str) -> ConfigResult<T> {
+    value
+        .parse()
+        .map_err(|_| ConfigError::Other(format!("{flag} expects a number, got {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(strategy: BotKind, rounds: u64) -> SimConfig {
+        let rules = Rules::new();
+        SimConfig {
+            chart: Arc::new(StrategyChart::generate(&rules)),
+            rules,
+            strategy,
+            rounds,
+            threads: 2,
+            seed: 11,
+            bankroll: 100,
+            compare: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn seeded_runs_repeat() {
+        let a = run(&config(BotKind::MimicDealer, 2_000)).unwrap();
+        let b = run(&config(BotKind::MimicDealer, 2_000)).unwrap();
+        assert_eq!(a.rounds, 2_000);
+        assert_eq!(a.sum, b.sum);
+        assert_eq!(a.sum_squares, b.sum_squares);
+    }
+
+    #[test]
+    fn basic_strategy_beats_mimicking_the_dealer() {
+        let basic = run(&config(BotKind::Basic, 40_000)).unwrap();
+        let mimic = run(&config(BotKind::MimicDealer, 40_000)).unwrap();
+
+        assert!(basic.ev().abs() < 4.0 * basic.standard_error() + 0.01);
+        assert!(mimic.ev() < basic.ev() - 0.02);
+        // a blackjack round's variance sits around 1.3 bets squared
+        assert!(
+            (1.0..1.6).contains(&basic.variance()),
+            "{}",
+            basic.variance()
+        );
+        assert_eq!(mimic.risk_of_ruin(), 1.0);
+    }
+
+    #[test]
+    fn parses_arguments() {
+        let args: Vec<String> = [
+            "--rounds",
+            "500",
+            "--threads",
+            "3",
+            "--preset",
+            "downtown",
+            "--strategy",
+            "never-bust",
+        ]
+        .map(String::from)
+        .to_vec();
+        let config = parse_args(&args).unwrap();
+        assert_eq!(config.rounds, 500);
+        assert_eq!(config.threads, 3);
+        assert_eq!(config.rules.num_decks, 2);
+        assert_eq!(config.strategy, BotKind::NeverBust);
+
+        assert!(parse_args(&["--strategy".into(), "psychic".into()]).is_err());
+        assert!(parse_args(&["--rounds".into()]).is_err());
+        assert!(parse_args(&["--bet".into(), "5".into()]).is_err());
+        let config = parse_args(&["--bet".into(), "25".into()]).unwrap();
+        assert_eq!(config.rules.min_bet, 25);
+    }
+
+    #[test]
+    fn continuous_shuffling_takes_away_the_counters_edge() {
+        let modes = [
+            ShuffleMode::CutCard {
+                penetration_percent: 85,
+            },
+            ShuffleMode::Continuous { buffer: 20 },
+        ];
+        let comparison = compare_shuffles(&config(BotKind::HiLoIndex, 40_000), &modes).unwrap();
+        let (cut, csm) = (&comparison.runs[0].1, &comparison.runs[1].1);
+        assert!(cut.ev() > csm.ev(), "{comparison}");
+        assert!(csm.ev() < 0.0, "{comparison}");
+        // with nothing to count between rounds, the counter flat bets
+        assert!(csm.variance() < 1.6, "{comparison}");
+        assert!(comparison.to_string().contains("csm:20"));
+    }
+
+    #[test]
+    fn parses_shuffle_options() {
+        let args: Vec<String> = ["--shuffle", "csm:40", "--compare", "cut:75,csm:20"]
+            .map(String::from)
+            .to_vec();
+        let config = parse_args(&args).unwrap();
+        assert_eq!(config.rules.shuffle, ShuffleMode::Continuous { buffer: 40 });
+        assert_eq!(config.compare.len(), 2);
+
+        assert!(parse_args(&["--shuffle".into(), "riffle".into()]).is_err());
+        assert!(parse_args(&["--compare".into(), "cut:0,csm:20".into()]).is_err());
+        assert!(parse_args(&["--shuffle".into(), "csm:999".into()]).is_err());
+    }
+
+    #[test]
+    fn report_statistics() {
+        let report = SimReport {
+            rounds: 4,
+            sum: 2.0,
+            sum_squares: 4.0,
+            elapsed: Duration::from_secs(2),
+            bankroll: 10,
+        };
+        assert_eq!(report.ev(), 0.5);
+        assert_eq!(report.variance(), 0.75);
+        assert!((report.standard_error() - (0.75f64 / 4.0).sqrt()).abs() < 1e-12);
+        assert!((report.risk_of_ruin() - (-2.0 * 0.5 * 10.0 / 0.75f64).exp()).abs() < 1e-12);
+        assert_eq!(report.hands_per_second(), 2.0);
+    }
+}
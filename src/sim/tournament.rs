@@ -0,0 +1,375 @@
+//! Head-to-head bot tournaments. Each entrant plays every shoe alone at its own table, and shoe
+//! `i` is shuffled from the same seed at every table, so all entrants start from identical
+//! cards. Comparing results shoe by shoe cancels the luck the entrants share, so the gap between
+//! two strategies shows up in fewer shoes than separate simulations would need, the more so the
+//! more alike they play.
+
+use crate::core::rules::Rules;
+use crate::engine::bot::{BotKind, BotStrategy, Bots};
+use crate::engine::game::Game;
+use crate::error::{ConfigError, ConfigResult, GameResult};
+use crate::sim::{load_rules, number};
+use crate::strategy::chart::StrategyChart;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Two-sided 95% normal quantile.
+const Z_95: f64 = 1.96;
+
+type Builder = dyn Fn(&Rules, u64) -> Box<dyn BotStrategy> + Send + Sync;
+
+/// A named way to build a fresh bot for each shoe from the rules and the shoe's seed.
+#[derive(Clone)]
+pub struct Entrant {
+    pub name: String,
+    build: Arc<Builder>,
+}
+
+impl Entrant {
+    pub fn new(
+        name: impl Into<String>,
+        build: impl Fn(&Rules, u64) -> Box<dyn BotStrategy> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            build: Arc::new(build),
+        }
+    }
+
+    pub fn kind(kind: BotKind, chart: Arc<StrategyChart>) -> Self {
+        Self::new(kind.name(), move |rules, seed| {
+            kind.build(rules, &chart, seed)
+        })
+    }
+}
+
+impl fmt::Debug for Entrant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entrant").field("name", &self.name).finish()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentConfig {
+    pub rules: Rules,
+    pub entrants: Vec<Entrant>,
+    pub shoes: u64,
+    pub threads: usize,
+    pub seed: u64,
+}
+
+/// One entrant's result on one shoe, in units of the table minimum.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct ShoeResult {
+    net: f64,
+    rounds: u64,
+}
+
+/// An entrant's line in the report. EVs are per round in units of the table minimum, each with
+/// the half-width of its 95% confidence interval.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub rounds: u64,
+    pub ev: f64,
+    pub ev_ci: f64,
+    pub behind_leader: f64, // paired over the same shoes
+    pub behind_leader_ci: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TournamentReport {
+    pub shoes: u64,
+    pub standings: Vec<Standing>, // best first
+    pub elapsed: Duration,
+}
+
+impl TournamentReport {
+    fn new(names: Vec<String>, results: Vec<Vec<ShoeResult>>, elapsed: Duration) -> Self {
+        let shoes = results.first().map_or(0, |r| r.len()) as u64;
+        let mut standings: Vec<(Standing, &[ShoeResult])> = names
+            .into_iter()
+            .zip(&results)
+            .map(|(name, shoes)| {
+                let (ev, ev_ci) = ratio_interval(shoes.iter().map(|s| (s.net, s.rounds as f64)));
+                let standing = Standing {
+                    name,
+                    rounds: shoes.iter().map(|s| s.rounds).sum(),
+                    ev,
+                    ev_ci,
+                    behind_leader: 0.0,
+                    behind_leader_ci: 0.0,
+                };
+                (standing, shoes.as_slice())
+            })
+            .collect();
+        standings.sort_by(|a, b| b.0.ev.total_cmp(&a.0.ev));
+
+        if let Some(leader) = standings.first().map(|(_, shoes)| shoes.to_vec()) {
+            for (standing, shoes) in standings.iter_mut().skip(1) {
+                // Per-shoe differences, per round played at either table.
+                let paired = leader
+                    .iter()
+                    .zip(shoes.iter())
+                    .map(|(l, s)| (l.net - s.net, (l.rounds + s.rounds) as f64 / 2.0));
+                let (gap, ci) = ratio_interval(paired);
+                standing.behind_leader = gap;
+                standing.behind_leader_ci = ci;
+            }
+        }
+
+        Self {
+            shoes,
+            standings: standings.into_iter().map(|(s, _)| s).collect(),
+            elapsed,
+        }
+    }
+}
+
+/// Mean of `x / y` over pairs as `sum x / sum y`, with the delta-method 95% half-width.
+fn ratio_interval(pairs: impl Iterator<Item = (f64, f64)>) -> (f64, f64) {
+    let pairs: Vec<(f64, f64)> = pairs.collect();
+    let n = pairs.len() as f64;
+    let (sum_x, sum_y) = pairs
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+    if n < 2.0 || sum_y == 0.0 {
+        return (sum_x / sum_y.max(1.0), f64::INFINITY);
+    }
+    let ratio = sum_x / sum_y;
+    let residuals = pairs
+        .iter()
+        .map(|&(x, y)| (x - ratio * y).powi(2))
+        .sum::<f64>();
+    let mean_y = sum_y / n;
+    let se = (residuals / (n - 1.0)).sqrt() / (mean_y * n.sqrt());
+    (ratio, Z_95 * se)
+}
+
+impl fmt::Display for TournamentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} shoes in {:.1}s",
+            self.shoes,
+            self.elapsed.as_secs_f64()
+        )?;
+        writeln!(
+            f,
+            "{:<4} {:<16} {:>10} {:>18} {:>20}",
+            "rank", "bot", "rounds", "ev/round", "behind leader"
+        )?;
+        for (rank, s) in self.standings.iter().enumerate() {
+            let behind = if rank == 0 {
+                "-".to_string()
+            } else {
+                format!(
+                    "{:.3}% ± {:.3}",
+                    s.behind_leader * 100.0,
+                    s.behind_leader_ci * 100.0
+                )
+            };
+            writeln!(
+                f,
+                "{:<4} {:<16} {:>10} {:>18} {:>20}",
+                rank + 1,
+                s.name,
+                s.rounds,
+                format!("{:+.3}% ± {:.3}", s.ev * 100.0, s.ev_ci * 100.0),
+                behind
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays `config.shoes` shoes for every entrant, split across the worker threads by shoe.
+pub fn run(config: &TournamentConfig) -> GameResult<TournamentReport> {
+    let started = Instant::now();
+    let threads = config.threads.max(1) as u64;
+    let chunks = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|i| {
+                let shoes = (i * config.shoes / threads)..((i + 1) * config.shoes / threads);
+                scope.spawn(move || -> GameResult<Vec<Vec<ShoeResult>>> {
+                    shoes.map(|shoe| play_shoe(config, shoe)).collect()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().expect("tournament worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    // Regroup from shoe-major to entrant-major.
+    let mut results = vec![Vec::with_capacity(config.shoes as usize); config.entrants.len()];
+    for chunk in chunks {
+        for shoe in chunk? {
+            for (entrant, result) in shoe.into_iter().enumerate() {
+                results[entrant].push(result);
+            }
+        }
+    }
+    let names = config.entrants.iter().map(|e| e.name.clone()).collect();
+    Ok(TournamentReport::new(names, results, started.elapsed()))
+}
+
+/// Every entrant's result on shoe `shoe`, each playing it through to the cut card alone.
+fn play_shoe(config: &TournamentConfig, shoe: u64) -> GameResult<Vec<ShoeResult>> {
+    let seed = config.seed.wrapping_add(shoe);
+    let unit = config.rules.min_bet.max(1) as f64;
+    config
+        .entrants
+        .iter()
+        .map(|entrant| {
+            let mut game = Game::new(config.rules.clone(), Some(seed))?;
+            let mut bots = Bots::new();
+            let strategy = (entrant.build)(&config.rules, seed);
+            let id = bots.seat(&mut game, &entrant.name, u32::MAX / 2, strategy)?;
+
+            let mut result = ShoeResult::default();
+            // A bot that keeps sitting out would otherwise never reach the cut card.
+            let most_rounds = game.shoe().remaining().len();
+            for _ in 0..most_rounds {
+                bots.play_round(&mut game)?;
+                let net: i64 = game
+                    .results()
+                    .iter()
+                    .filter(|r| r.player_id == id)
+                    .map(|r| r.returned as i64 - r.wagered as i64)
+                    .sum();
+                result.net += net as f64 / unit;
+                result.rounds += 1;
+                if game.shoe().needs_reshuffle() {
+                    break;
+                }
+            }
+            Ok(result)
+        })
+        .collect()
+}
+
+/// `blackjack tournament` arguments:
+/// `--bots NAME,NAME,... --shoes N --threads N --seed N --preset NAME --config PATH`.
+pub fn parse_args(args: &[String]) -> ConfigResult<TournamentConfig> {
+    let mut shoes = 1000;
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut seed = 1;
+    let mut kinds = vec![BotKind::Basic, BotKind::HiLoIndex, BotKind::MimicDealer];
+    let mut rules = Rules::new();
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| ConfigError::Other(format!("{flag} needs a value")))?;
+        match flag.as_str() {
+            "--bots" => {
+                kinds = value
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<ConfigResult<_>>()?
+            }
+            "--shoes" => shoes = number(flag, value)?,
+            "--threads" => threads = number(flag, value)?,
+            "--seed" => seed = number(flag, value)?,
+            "--preset" => rules = Rules::preset(value)?,
+            "--config" => rules = load_rules(value)?,
+            other => return Err(ConfigError::Other(format!("unknown option: {other}"))),
+        }
+    }
+    rules.validate()?;
+    if kinds.len() < 2 {
+        return Err(ConfigError::Other(
+            "a tournament needs at least two bots".into(),
+        ));
+    }
+
+    let chart = Arc::new(StrategyChart::generate(&rules));
+    Ok(TournamentConfig {
+        entrants: kinds
+            .into_iter()
+            .map(|kind| Entrant::kind(kind, Arc::clone(&chart)))
+            .collect(),
+        rules,
+        shoes,
+        threads,
+        seed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::bot::MimicDealerBot;
+
+    fn config(entrants: Vec<Entrant>, shoes: u64) -> TournamentConfig {
+        TournamentConfig {
+            rules: Rules::new(),
+            entrants,
+            shoes,
+            threads: 3,
+            seed: 21,
+        }
+    }
+
+    fn mimic(name: &str) -> Entrant {
+        Entrant::new(name, |_, _| Box::new(MimicDealerBot))
+    }
+
+    #[test]
+    fn identical_strategies_tie_on_identical_cards() {
+        let report = run(&config(vec![mimic("a"), mimic("b")], 40)).unwrap();
+        assert_eq!(report.shoes, 40);
+        let [a, b] = &report.standings[..] else {
+            panic!("two standings expected");
+        };
+        assert_eq!(a.rounds, b.rounds);
+        assert_eq!(a.ev, b.ev);
+        assert_eq!(b.behind_leader, 0.0);
+        assert_eq!(b.behind_leader_ci, 0.0);
+        assert!(a.ev_ci > 0.0);
+    }
+
+    #[test]
+    fn pairing_narrows_the_gap_between_strategies() {
+        let chart = Arc::new(StrategyChart::generate(&Rules::new()));
+        let entrants = vec![
+            mimic("mimic"),
+            Entrant::kind(BotKind::Basic, Arc::clone(&chart)),
+        ];
+        let report = run(&config(entrants, 600)).unwrap();
+        let leader = &report.standings[0];
+        let trailer = &report.standings[1];
+
+        assert_eq!(leader.name, "basic");
+        assert!(trailer.behind_leader > 0.0);
+        // Shared cards make the paired interval tighter than comparing independent runs.
+        let unpaired = (leader.ev_ci.powi(2) + trailer.ev_ci.powi(2)).sqrt();
+        assert!(trailer.behind_leader_ci < unpaired);
+        assert!(report.to_string().contains("basic"));
+    }
+
+    #[test]
+    fn parses_arguments() {
+        let args: Vec<String> = ["--bots", "basic,paroli", "--shoes", "50", "--seed", "4"]
+            .map(String::from)
+            .to_vec();
+        let config = parse_args(&args).unwrap();
+        assert_eq!(config.shoes, 50);
+        let names: Vec<&str> = config.entrants.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["basic", "paroli"]);
+
+        assert!(parse_args(&["--bots".into(), "basic".into()]).is_err());
+        assert!(parse_args(&["--bots".into(), "basic,oracle".into()]).is_err());
+    }
+
+    #[test]
+    fn ratio_interval_of_constant_shoes_is_exact() {
+        let (ev, ci) = ratio_interval([(2.0, 4.0), (4.0, 8.0), (1.0, 2.0)].into_iter());
+        assert_eq!(ev, 0.5);
+        assert_eq!(ci, 0.0);
+    }
+}
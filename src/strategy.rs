@@ -0,0 +1,4 @@
+pub mod chart;
+pub mod composition;
+pub mod count;
+pub mod ev;
@@ -0,0 +1,478 @@
+//! Total-dependent basic strategy, derived from the EV engine for whatever rules are in force.
+//! Each cell averages the EVs of every two-card hand making that total, weighted by how likely
+//! the shoe is to deal it, so a hard 12 is played the same whether it is 10-2 or 7-5.
+
+use crate::core::card::{Card, Rank, Suit};
+use crate::core::hand::Hand;
+use crate::core::rules::Rules;
+use crate::strategy::composition::ShoeComposition;
+use crate::strategy::ev::{ActionEv, EvCalculator};
+use crate::types::action::Action;
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Cell, Row, Table};
+use std::fmt;
+
+/// Dealer upcards in chart order, by pip value: 2 through 10, then the Ace.
+pub const UPCARDS: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 1];
+
+/// What a chart cell says to do. Doubles fall back to a hit or a stand where doubling is not
+/// allowed, the way printed charts mark them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Play {
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+    Split,
+    Surrender,
+}
+
+impl Play {
+    /// The conventional chart abbreviation.
+    pub fn code(self) -> &'static str {
+        match self {
+            Play::Hit => "H",
+            Play::Stand => "S",
+            Play::DoubleOrHit => "Dh",
+            Play::DoubleOrStand => "Ds",
+            Play::Split => "P",
+            Play::Surrender => "R",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Play::Hit => Color::Red,
+            Play::Stand => Color::Yellow,
+            Play::DoubleOrHit | Play::DoubleOrStand => Color::Cyan,
+            Play::Split => Color::Green,
+            Play::Surrender => Color::Magenta,
+        }
+    }
+}
+
+impl fmt::Display for Play {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChartKind {
+    Hard,
+    Soft,
+    Pairs,
+    Surrender,
+}
+
+impl ChartKind {
+    pub const ALL: [ChartKind; 4] = [
+        ChartKind::Hard,
+        ChartKind::Soft,
+        ChartKind::Pairs,
+        ChartKind::Surrender,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartKind::Hard => "hard",
+            ChartKind::Soft => "soft",
+            ChartKind::Pairs => "pairs",
+            ChartKind::Surrender => "surrender",
+        }
+    }
+}
+
+/// One row of a chart: the player's hand label and a play per upcard, in [`UPCARDS`] order.
+/// In the surrender chart `None` means play on as the hard chart says.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChartRow {
+    pub label: String,
+    pub plays: [Option<Play>; 10],
+}
+
+/// Basic strategy for one set of rules: hard totals 5-19, soft 13-20, every pair, and where
+/// surrender beats playing on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StrategyChart {
+    pub hard: Vec<ChartRow>,
+    pub soft: Vec<ChartRow>,
+    pub pairs: Vec<ChartRow>,
+    pub surrender: Vec<ChartRow>,
+}
+
+impl StrategyChart {
+    pub fn generate(rules: &Rules) -> Self {
+        let mut calc = EvCalculator::new(rules);
+        let shoe = ShoeComposition::from_rules(rules);
+        let surrender_offered = rules.surrender.allows_late();
+
+        let mut hard = Vec::new();
+        let mut surrender = Vec::new();
+        for total in 5..=19u8 {
+            let hands: Vec<[u8; 2]> = (2..=9u8)
+                .filter_map(|a| {
+                    let b = total.checked_sub(a)?;
+                    (a < b && b <= 10).then_some([a, b])
+                })
+                .collect();
+            let evs = UPCARDS.map(|up| averaged(&mut calc, &shoe, &hands, up));
+            hard.push(ChartRow {
+                label: total.to_string(),
+                plays: evs.map(|ev| Some(play(&ev, false))),
+            });
+            if surrender_offered && total >= 12 {
+                surrender.push(ChartRow {
+                    label: total.to_string(),
+                    plays: evs
+                        .map(|ev| (ev.best().0 == Action::Surrender).then_some(Play::Surrender)),
+                });
+            }
+        }
+
+        let soft = (2..=9u8)
+            .map(|kicker| ChartRow {
+                label: format!("A,{kicker}"),
+                plays: UPCARDS
+                    .map(|up| Some(play(&averaged(&mut calc, &shoe, &[[1, kicker]], up), false))),
+            })
+            .collect();
+
+        let pairs = (2..=10u8)
+            .chain([1])
+            .map(|value| ChartRow {
+                label: pair_label(value),
+                plays: UPCARDS.map(|up| {
+                    Some(play(
+                        &averaged(&mut calc, &shoe, &[[value, value]], up),
+                        true,
+                    ))
+                }),
+            })
+            .collect();
+
+        Self {
+            hard,
+            soft,
+            pairs,
+            surrender,
+        }
+    }
+
+    pub fn rows(&self, kind: ChartKind) -> &[ChartRow] {
+        match kind {
+            ChartKind::Hard => &self.hard,
+            ChartKind::Soft => &self.soft,
+            ChartKind::Pairs => &self.pairs,
+            ChartKind::Surrender => &self.surrender,
+        }
+    }
+
+    /// The play for a hand against an upcard, looked up the way a player reads the charts:
+    /// surrender first, then pairs, soft and hard totals.
+    pub fn lookup(&self, hand: &Hand, upcard: Card) -> Option<Play> {
+        let column = UPCARDS.iter().position(|&v| v == upcard.pip_value())?;
+        let value = hand.value();
+        let label = value.to_string();
+        let find = |rows: &[ChartRow], label: &str| {
+            rows.iter()
+                .find(|r| r.label == label)
+                .and_then(|r| r.plays[column])
+        };
+
+        if hand.cards().len() == 2 {
+            // The surrender chart was worked out from non-pair hands.
+            let surrenders = !hand.is_soft() && !hand.is_pair();
+            if let Some(play) = find(&self.surrender, &label).filter(|_| surrenders) {
+                return Some(play);
+            }
+            if hand.is_pair() {
+                return find(&self.pairs, &pair_label(hand.cards()[0].pip_value()));
+            }
+        }
+        self.lookup_total(hand, upcard)
+    }
+
+    /// The play for a hand by its total alone, from the soft and hard charts. Soft 12 has no
+    /// row and is always hit.
+    pub fn lookup_total(&self, hand: &Hand, upcard: Card) -> Option<Play> {
+        let column = UPCARDS.iter().position(|&v| v == upcard.pip_value())?;
+        let find = |rows: &[ChartRow], label: &str| {
+            rows.iter()
+                .find(|r| r.label == label)
+                .and_then(|r| r.plays[column])
+        };
+
+        let value = hand.value();
+        if hand.is_soft() {
+            return match value {
+                // A,A that cannot be split: no card can bust it.
+                12 => Some(Play::Hit),
+                21 => Some(Play::Stand),
+                _ => find(&self.soft, &format!("A,{}", value - 11)),
+            };
+        }
+        match value {
+            0..=4 => Some(Play::Hit),
+            20.. => Some(Play::Stand),
+            _ => find(&self.hard, &value.to_string()),
+        }
+    }
+
+    /// All four charts as aligned plain text.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for kind in ChartKind::ALL {
+            let rows = self.rows(kind);
+            if rows.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("{}\n{:>5}", kind.name(), ""));
+            for up in UPCARDS {
+                out.push_str(&format!("{:>4}", upcard_label(up)));
+            }
+            out.push('\n');
+            for row in rows {
+                out.push_str(&format!("{:>5}", row.label));
+                for play in row.plays {
+                    out.push_str(&format!("{:>4}", play.map_or("-", Play::code)));
+                }
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// One line per row: chart, player hand (quoted, as pair labels hold a comma), then a play
+    /// per upcard.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("chart,hand");
+        for up in UPCARDS {
+            out.push(',');
+            out.push_str(upcard_label(up));
+        }
+        out.push('\n');
+        for kind in ChartKind::ALL {
+            for row in self.rows(kind) {
+                out.push_str(&format!("{},\"{}\"", kind.name(), row.label));
+                for play in row.plays {
+                    out.push(',');
+                    out.push_str(play.map_or("", Play::code));
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// One chart as a colour-coded ratatui table.
+    pub fn to_table(&self, kind: ChartKind) -> Table<'static> {
+        let header = Row::new(
+            std::iter::once(Cell::from("")).chain(UPCARDS.map(|up| Cell::from(upcard_label(up)))),
+        )
+        .style(Style::default().fg(Color::White));
+
+        let rows = self.rows(kind).iter().map(|row| {
+            Row::new(
+                std::iter::once(Cell::from(row.label.clone())).chain(row.plays.map(|play| {
+                    match play {
+                        Some(play) => Cell::from(Line::from(play.code()))
+                            .style(Style::default().fg(Color::Black).bg(play.color())),
+                        None => Cell::from("-"),
+                    }
+                })),
+            )
+        });
+
+        let mut widths = vec![ratatui::layout::Constraint::Length(5)];
+        widths.extend([ratatui::layout::Constraint::Length(3); 10]);
+        Table::new(rows, widths).header(header)
+    }
+}
+
+fn upcard_label(value: u8) -> &'static str {
+    match value {
+        1 => "A",
+        2 => "2",
+        3 => "3",
+        4 => "4",
+        5 => "5",
+        6 => "6",
+        7 => "7",
+        8 => "8",
+        9 => "9",
+        _ => "T",
+    }
+}
+
+fn pair_label(value: u8) -> String {
+    let label = upcard_label(value);
+    format!("{label},{label}")
+}
+
+fn card(value: u8) -> Card {
+    let rank = match value {
+        1 => Rank::Ace,
+        10 => Rank::Ten,
+        v => Rank::all()[(v - 2) as usize],
+    };
+    Card::new(rank, Suit::Spades)
+}
+
+// EVs for a total, averaged over the two-card hands that make it, weighted by their odds.
+fn averaged(
+    calc: &mut EvCalculator,
+    shoe: &ShoeComposition,
+    hands: &[[u8; 2]],
+    up: u8,
+) -> ActionEv {
+    let upcard = card(up);
+    let shoe = shoe.without(&[upcard]);
+    let mut weighted: Option<(f64, ActionEv)> = None;
+
+    for &[a, b] in hands {
+        let weight = if a == b {
+            shoe.count(a) as f64 * (shoe.count(a) as f64 - 1.0)
+        } else {
+            2.0 * shoe.count(a) as f64 * shoe.count(b) as f64
+        };
+        if weight == 0.0 {
+            continue;
+        }
+        let mut hand = Hand::new();
+        hand.add_card(card(a));
+        hand.add_card(card(b));
+        let ev = calc.evaluate(&shoe.without(hand.cards()), &hand, upcard);
+
+        weighted = Some(match weighted {
+            None => (weight, ev),
+            Some((total, acc)) => (total + weight, blend(acc, total, ev, weight)),
+        });
+    }
+    weighted
+        .map(|(_, ev)| ev)
+        .expect("every chart total has a hand")
+}
+
+fn blend(a: ActionEv, wa: f64, b: ActionEv, wb: f64) -> ActionEv {
+    let mix = |x: f64, y: f64| (x * wa + y * wb) / (wa + wb);
+    let mix_opt = |x: Option<f64>, y: Option<f64>| Some(mix(x?, y?));
+    ActionEv {
+        stand: mix(a.stand, b.stand),
+        hit: mix_opt(a.hit, b.hit),
+        double: mix_opt(a.double, b.double),
+        split: mix_opt(a.split, b.split),
+        surrender: mix_opt(a.surrender, b.surrender),
+    }
+}
+
+// The chart play: surrender has its own chart, so it is left out here.
+fn play(ev: &ActionEv, pair: bool) -> Play {
+    let on = ActionEv {
+        surrender: None,
+        split: ev.split.filter(|_| pair),
+        ..*ev
+    };
+    let fallback = match on.hit {
+        Some(hit) if hit > on.stand => Play::Hit,
+        _ => Play::Stand,
+    };
+    match on.best().0 {
+        Action::Split => Play::Split,
+        Action::Double if fallback == Play::Hit => Play::DoubleOrHit,
+        Action::Double => Play::DoubleOrStand,
+        Action::Hit => Play::Hit,
+        _ => Play::Stand,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::payout::BlackjackPayout;
+    use crate::core::rules::SurrenderRule;
+
+    fn cell(rows: &[ChartRow], label: &str, up: u8) -> Option<Play> {
+        let column = UPCARDS.iter().position(|&v| v == up).unwrap();
+        rows.iter().find(|r| r.label == label).unwrap().plays[column]
+    }
+
+    #[test]
+    fn six_deck_s17_das_matches_published_chart() {
+        let rules = Rules {
+            surrender: SurrenderRule::Late,
+            split_limit: 3,
+            ..Rules::new()
+        };
+        let chart = StrategyChart::generate(&rules);
+
+        assert_eq!(cell(&chart.hard, "16", 10), Some(Play::Hit));
+        assert_eq!(cell(&chart.hard, "12", 4), Some(Play::Stand));
+        assert_eq!(cell(&chart.hard, "12", 3), Some(Play::Hit));
+        assert_eq!(cell(&chart.hard, "11", 10), Some(Play::DoubleOrHit));
+        assert_eq!(cell(&chart.hard, "9", 2), Some(Play::Hit));
+        assert_eq!(cell(&chart.soft, "A,7", 3), Some(Play::DoubleOrStand));
+        assert_eq!(cell(&chart.soft, "A,7", 9), Some(Play::Hit));
+        assert_eq!(cell(&chart.pairs, "8,8", 10), Some(Play::Split));
+        assert_eq!(cell(&chart.pairs, "T,T", 6), Some(Play::Stand));
+        assert_eq!(cell(&chart.pairs, "4,4", 5), Some(Play::Split));
+        assert_eq!(cell(&chart.surrender, "16", 10), Some(Play::Surrender));
+        assert_eq!(cell(&chart.surrender, "16", 6), None);
+        assert_eq!(cell(&chart.surrender, "15", 10), Some(Play::Surrender));
+    }
+
+    #[test]
+    fn rules_change_the_chart() {
+        let h17 = StrategyChart::generate(&Rules {
+            dealer_hits_soft_17: true,
+            split_limit: 3,
+            ..Rules::new()
+        });
+        // H17 doubles 11 against an Ace, S17 six-deck just hits
+        assert_eq!(cell(&h17.hard, "11", 1), Some(Play::DoubleOrHit));
+        assert!(h17.surrender.is_empty());
+
+        let no_das = StrategyChart::generate(&Rules {
+            double_after_split_allowed: false,
+            blackjack_payout: BlackjackPayout::Vegas,
+            ..Rules::new()
+        });
+        // 4,4 against a 5 only splits when the 4s can be doubled afterwards
+        assert_eq!(cell(&no_das.pairs, "4,4", 5), Some(Play::Hit));
+    }
+
+    #[test]
+    fn exports() {
+        let chart = StrategyChart::generate(&Rules::new());
+        let text = chart.to_text();
+        assert!(text.starts_with("hard\n"));
+        assert!(text.contains("  A,A"));
+
+        let csv = chart.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("chart,hand,2,3,4,5,6,7,8,9,T,A"));
+        assert_eq!(
+            csv.lines().count(),
+            1 + chart.hard.len() + chart.soft.len() + chart.pairs.len()
+        );
+        assert!(csv.contains("pairs,\"8,8\",P,P,P,P,P,P,P,P,P,P\n"));
+
+        let _table = chart.to_table(ChartKind::Pairs);
+    }
+
+    #[test]
+    fn lookup_reads_the_right_chart() {
+        let chart = StrategyChart::generate(&Rules::new());
+        let hand = |a: u8, b: u8| {
+            let mut hand = Hand::new();
+            hand.add_card(card(a));
+            hand.add_card(card(b));
+            hand
+        };
+        assert_eq!(chart.lookup(&hand(8, 8), card(10)), Some(Play::Split));
+        assert_eq!(chart.lookup(&hand(1, 7), card(9)), Some(Play::Hit));
+        assert_eq!(chart.lookup(&hand(10, 7), card(1)), Some(Play::Stand));
+    }
+}
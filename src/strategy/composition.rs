@@ -0,0 +1,95 @@
+use crate::core::card::Card;
+use crate::core::rules::Rules;
+
+/// Cards left to be dealt, counted by blackjack value: index 0 is the Ace, 9 every ten-valued
+/// card. Suits and picture ranks never change a decision, so this is all the EV engine needs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShoeComposition {
+    counts: [u16; 10],
+}
+
+impl ShoeComposition {
+    /// A full shoe as the rules build it.
+    pub fn from_rules(rules: &Rules) -> Self {
+        let mut composition = Self::from_cards(&rules.deck().cards());
+        for count in &mut composition.counts {
+            *count *= rules.num_decks as u16;
+        }
+        composition
+    }
+
+    pub fn from_cards(cards: &[Card]) -> Self {
+        let mut composition = Self { counts: [0; 10] };
+        for card in cards {
+            composition.counts[index(card.pip_value())] += 1;
+        }
+        composition
+    }
+
+    /// Takes a seen card out of the shoe. Removing a value that has run out is ignored.
+    pub fn remove(&mut self, card: Card) {
+        self.remove_value(card.pip_value());
+    }
+
+    pub fn without(mut self, cards: &[Card]) -> Self {
+        for &card in cards {
+            self.remove(card);
+        }
+        self
+    }
+
+    /// Cards of pip value `value` (1 for an Ace, 10 for any ten-valued card).
+    pub fn count(&self, value: u8) -> u16 {
+        self.counts[index(value)]
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().map(|&c| c as u32).sum()
+    }
+
+    pub(crate) fn remove_value(&mut self, value: u8) {
+        let count = &mut self.counts[index(value)];
+        *count = count.saturating_sub(1);
+    }
+
+    pub(crate) fn counts(&self) -> [u16; 10] {
+        self.counts
+    }
+}
+
+fn index(value: u8) -> usize {
+    (value.clamp(1, 10) - 1) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::{Rank, Suit};
+
+    #[test]
+    fn six_deck_shoe() {
+        let shoe = ShoeComposition::from_rules(&Rules::new());
+        assert_eq!(shoe.total(), 312);
+        assert_eq!(shoe.count(10), 96);
+        assert_eq!(shoe.count(1), 24);
+    }
+
+    #[test]
+    fn spanish_shoe_has_fewer_tens() {
+        let shoe = ShoeComposition::from_rules(&Rules::spanish_21());
+        assert_eq!(shoe.count(10), 72);
+        assert_eq!(shoe.total(), 288);
+    }
+
+    #[test]
+    fn removing_cards() {
+        let shoe = ShoeComposition::from_cards(&Card::standard_deck()).without(&[
+            Card::new(Rank::King, Suit::Clubs),
+            Card::new(Rank::Ten, Suit::Hearts),
+            Card::new(Rank::Ace, Suit::Spades),
+        ]);
+        assert_eq!(shoe.count(10), 14);
+        assert_eq!(shoe.count(1), 3);
+        assert_eq!(shoe.total(), 49);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::core::card::{Card, Rank};
+use crate::core::shoe::Shoe;
+use crate::error::{ConfigError, ConfigResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A card counting system: a tag for every rank, added up as cards are seen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CountSystem {
+    HiLo,
+    Ko, // unbalanced: a full deck counts +4
+    HiOptI,
+    HiOptII,
+    OmegaII,
+    Zen,
+    WongHalves,
+}
+
+impl CountSystem {
+    pub const ALL: [CountSystem; 7] = [
+        CountSystem::HiLo,
+        CountSystem::Ko,
+        CountSystem::HiOptI,
+        CountSystem::HiOptII,
+        CountSystem::OmegaII,
+        CountSystem::Zen,
+        CountSystem::WongHalves,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CountSystem::HiLo => "hi-lo",
+            CountSystem::Ko => "ko",
+            CountSystem::HiOptI => "hi-opt-i",
+            CountSystem::HiOptII => "hi-opt-ii",
+            CountSystem::OmegaII => "omega-ii",
+            CountSystem::Zen => "zen",
+            CountSystem::WongHalves => "wong-halves",
+        }
+    }
+
+    /// Tags for 2 through 9, then ten-valued cards, then the Ace.
+    fn tags(self) -> [f64; 10] {
+        match self {
+            CountSystem::HiLo => [1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0, -1.0],
+            CountSystem::Ko => [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, -1.0, -1.0],
+            CountSystem::HiOptI => [0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+            CountSystem::HiOptII => [1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 0.0, 0.0, -2.0, 0.0],
+            CountSystem::OmegaII => [1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, -1.0, -2.0, 0.0],
+            CountSystem::Zen => [1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, 0.0, -2.0, -1.0],
+            CountSystem::WongHalves => [0.5, 1.0, 1.0, 1.5, 1.0, 0.5, 0.0, -0.5, -1.0, -1.0],
+        }
+    }
+
+    pub fn tag(self, rank: Rank) -> f64 {
+        match rank.pip_value() {
+            1 => self.tags()[9],
+            value => self.tags()[value as usize - 2],
+        }
+    }
+
+    /// Whether a full deck counts back to zero, so the running count can be converted to a
+    /// true count.
+    pub fn is_balanced(self) -> bool {
+        self != CountSystem::Ko
+    }
+
+    /// Where the running count starts off the shuffle. KO starts below zero so that its key
+    /// count lands at +4 whatever the number of decks.
+    pub fn initial_running_count(self, num_decks: u8) -> f64 {
+        if self.is_balanced() {
+            0.0
+        } else {
+            -4.0 * (num_decks as f64 - 1.0)
+        }
+    }
+}
+
+impl fmt::Display for CountSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CountSystem {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> ConfigResult<Self> {
+        let wanted = s.trim().to_ascii_lowercase().replace([' ', '_'], "-");
+        CountSystem::ALL
+            .into_iter()
+            .find(|c| c.name() == wanted)
+            .ok_or_else(|| ConfigError::UnknownCountSystem(s.to_string()))
+    }
+}
+
+/// A running count kept through a shoe. Reset it whenever the shoe is shuffled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counter {
+    system: CountSystem,
+    num_decks: u8,
+    running: f64,
+}
+
+impl Counter {
+    pub fn new(system: CountSystem, num_decks: u8) -> Self {
+        Self {
+            system,
+            num_decks,
+            running: system.initial_running_count(num_decks),
+        }
+    }
+
+    pub fn system(&self) -> CountSystem {
+        self.system
+    }
+
+    pub fn see(&mut self, card: Card) {
+        self.running += self.system.tag(card.rank);
+    }
+
+    pub fn see_all(&mut self, cards: &[Card]) {
+        for &card in cards {
+            self.see(card);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.running = self.system.initial_running_count(self.num_decks);
+    }
+
+    pub fn running_count(&self) -> f64 {
+        self.running
+    }
+
+    /// Running count per deck left in the shoe. Unbalanced systems are played straight off
+    /// the running count, so for them this is the running count unchanged.
+    pub fn true_count(&self, shoe: &Shoe) -> f64 {
+        self.true_count_with(shoe.decks_remaining())
+    }
+
+    /// As [`Counter::true_count`], for a player who estimates the decks left themselves.
+    pub fn true_count_with(&self, decks_remaining: f64) -> f64 {
+        if !self.system.is_balanced() {
+            return self.running;
+        }
+        // Floored at half a deck so the last few cards cannot blow the count up.
+        self.running / decks_remaining.max(0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::Suit;
+
+    fn deck_total(system: CountSystem) -> f64 {
+        Card::standard_deck()
+            .iter()
+            .map(|c| system.tag(c.rank))
+            .sum()
+    }
+
+    #[test]
+    fn balanced_systems_net_to_zero() {
+        for system in CountSystem::ALL {
+            let total = deck_total(system);
+            if system.is_balanced() {
+                assert_eq!(total, 0.0, "{system}");
+            } else {
+                assert_eq!(total, 4.0, "{system}");
+            }
+        }
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(CountSystem::HiLo.tag(Rank::Queen), -1.0);
+        assert_eq!(CountSystem::HiOptI.tag(Rank::Ace), 0.0);
+        assert_eq!(CountSystem::OmegaII.tag(Rank::Nine), -1.0);
+        assert_eq!(CountSystem::Zen.tag(Rank::Four), 2.0);
+        assert_eq!(CountSystem::WongHalves.tag(Rank::Five), 1.5);
+        assert_eq!(CountSystem::Ko.tag(Rank::Seven), 1.0);
+    }
+
+    #[test]
+    fn ko_reaches_its_key_count_over_a_full_shoe() {
+        let mut counter = Counter::new(CountSystem::Ko, 6);
+        assert_eq!(counter.running_count(), -20.0);
+        for _ in 0..6 {
+            counter.see_all(&Card::standard_deck());
+        }
+        assert_eq!(counter.running_count(), 4.0);
+        counter.reset();
+        assert_eq!(counter.running_count(), -20.0);
+    }
+
+    #[test]
+    fn true_count_divides_by_decks_left() {
+        let mut shoe = Shoe::new(2, 100, Some(3)).unwrap();
+        let mut counter = Counter::new(CountSystem::HiLo, 2);
+        for _ in 0..52 {
+            counter.see(shoe.deal().unwrap());
+        }
+        assert_eq!(counter.true_count(&shoe), counter.running_count());
+
+        counter.see_all(&[Card::new(Rank::Five, Suit::Clubs); 3]);
+        assert_eq!(counter.true_count_with(0.25), counter.running_count() * 2.0);
+
+        let mut ko = Counter::new(CountSystem::Ko, 2);
+        ko.see(Card::new(Rank::Two, Suit::Clubs));
+        assert_eq!(ko.true_count_with(0.5), -3.0);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for system in CountSystem::ALL {
+            assert_eq!(system.name().parse::<CountSystem>().unwrap(), system);
+        }
+        assert_eq!(
+            "Wong Halves".parse::<CountSystem>().unwrap(),
+            CountSystem::WongHalves
+        );
+        assert!(matches!(
+            "red-seven".parse::<CountSystem>(),
+            Err(ConfigError::UnknownCountSystem(_))
+        ));
+    }
+}
@@ -0,0 +1,681 @@
+//! Exact expected values by recursion over every card the shoe can still produce. The dealer's
+//! final-total probabilities are worked out for each composition the player's draws can reach,
+//! so card removal is fully accounted for within a hand. Two simplifications, both standard:
+//! a split pair is valued as two independent hands without resplits, and variant bonus payouts
+//! (Spanish 21 21s, five-card tricks, free chips) are left out. With a peek, EVs are
+//! conditional on the dealer not holding a natural. Under Double Exposure the player sees the
+//! hole card too, so hands there are valued with [`EvCalculator::evaluate_exposed`];
+//! [`EvCalculator::evaluate`] averages over a hole card that is in fact known.
+
+use crate::core::card::Card;
+use crate::core::hand::Hand;
+use crate::core::payout::PayoutRatio;
+use crate::core::rules::{HoleCardRule, Rules, Variant};
+use crate::strategy::composition::ShoeComposition;
+use crate::types::action::Action;
+use std::collections::HashMap;
+
+const TWENTY_TWO: usize = 5;
+const OVER_22: usize = 6;
+const NATURAL: usize = 7;
+
+/// How the dealer's hand finishes, as probabilities of 17 to 21, 22, over 22 and a natural.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DealerOutcomes([f64; 8]);
+
+impl DealerOutcomes {
+    /// Probability of finishing on `total`, for totals 17 to 21.
+    pub fn total(&self, total: u8) -> f64 {
+        match total {
+            17..=21 => self.0[(total - 17) as usize],
+            _ => 0.0,
+        }
+    }
+
+    pub fn bust(&self) -> f64 {
+        self.0[TWENTY_TWO] + self.0[OVER_22]
+    }
+
+    pub fn twenty_two(&self) -> f64 {
+        self.0[TWENTY_TWO]
+    }
+
+    pub fn natural(&self) -> f64 {
+        self.0[NATURAL]
+    }
+}
+
+/// Expected value of each option on a hand, in units of the original bet. `None` marks an
+/// option the rules or the hand do not allow.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ActionEv {
+    pub stand: f64,
+    pub hit: Option<f64>,
+    pub double: Option<f64>,
+    pub split: Option<f64>,
+    pub surrender: Option<f64>,
+}
+
+impl ActionEv {
+    pub fn ev(&self, action: &Action) -> Option<f64> {
+        match action {
+            Action::Stand => Some(self.stand),
+            Action::Hit => self.hit,
+            Action::Double => self.double,
+            Action::Split => self.split,
+            Action::Surrender => self.surrender,
+            _ => None,
+        }
+    }
+
+    /// The option with the highest EV. Ties go to the simpler play, in the order listed.
+    pub fn best(&self) -> (Action, f64) {
+        let options = [
+            (Action::Stand, Some(self.stand)),
+            (Action::Hit, self.hit),
+            (Action::Double, self.double),
+            (Action::Split, self.split),
+            (Action::Surrender, self.surrender),
+        ];
+        options
+            .into_iter()
+            .filter_map(|(action, ev)| ev.map(|ev| (action, ev)))
+            .fold((Action::Stand, self.stand), |best, option| {
+                if option.1 > best.1 + 1e-12 {
+                    option
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// What choosing `action` gives up against the best play, for trainer feedback.
+    pub fn regret(&self, action: &Action) -> Option<f64> {
+        Some(self.best().1 - self.ev(action)?)
+    }
+}
+
+// A hand's total as the recursion tracks it: the hard count plus whether an Ace can count 11.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Total {
+    hard: u8,
+    ace: bool,
+}
+
+impl Total {
+    fn of(hand: &Hand) -> Self {
+        let cards = hand.cards();
+        Self {
+            hard: cards.iter().map(|c| c.pip_value()).sum(),
+            ace: cards.iter().any(|c| c.pip_value() == 1),
+        }
+    }
+
+    fn add(self, value: u8) -> Self {
+        Self {
+            hard: self.hard + value,
+            ace: self.ace || value == 1,
+        }
+    }
+
+    fn value(self) -> u8 {
+        if self.ace && self.hard + 10 <= 21 {
+            self.hard + 10
+        } else {
+            self.hard
+        }
+    }
+
+    fn is_soft(self) -> bool {
+        self.ace && self.hard + 10 <= 21
+    }
+}
+
+// The dealer's cards the player can see: the upcard, or both under Double Exposure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Showing {
+    total: Total,
+    cards: u8,
+}
+
+type DealerKey = ([u16; 10], Total, u8);
+type PlayerKey = ([u16; 10], Total, Showing, u8);
+
+/// Exact EV calculator for one set of rules. Results are cached by shoe composition, so one
+/// calculator should be kept and reused across decisions.
+#[derive(Clone, Debug)]
+pub struct EvCalculator {
+    rules: Rules,
+    natural_ratio: f64,
+    dealer: HashMap<DealerKey, [f64; 8]>,
+    player: HashMap<PlayerKey, f64>,
+}
+
+impl EvCalculator {
+    pub fn new(rules: &Rules) -> Self {
+        let ratio = rules.natural_payout().unwrap_or(PayoutRatio::EVEN_MONEY);
+        Self {
+            rules: rules.clone(),
+            natural_ratio: ratio.numerator() as f64 / ratio.denominator() as f64,
+            dealer: HashMap::new(),
+            player: HashMap::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Drops every cached result.
+    pub fn clear(&mut self) {
+        self.dealer.clear();
+        self.player.clear();
+    }
+
+    /// The dealer's final-hand probabilities with `upcard` showing. `shoe` holds the unseen
+    /// cards, with the upcard already taken out.
+    pub fn dealer_outcomes(&mut self, shoe: &ShoeComposition, upcard: Card) -> DealerOutcomes {
+        let up = Total::of(&single(upcard));
+        DealerOutcomes(self.dealer_from(shoe.counts(), up, 1))
+    }
+
+    /// EVs of every option on `hand` against `upcard`. `shoe` holds the unseen cards, with the
+    /// player's cards and the upcard already taken out.
+    pub fn evaluate(&mut self, shoe: &ShoeComposition, hand: &Hand, upcard: Card) -> ActionEv {
+        let showing = Showing {
+            total: Total::of(&single(upcard)),
+            cards: 1,
+        };
+        self.evaluate_showing(shoe, hand, upcard, showing)
+    }
+
+    /// Double Exposure: EVs against both dealer cards, with `hole` taken out of `shoe` as well.
+    pub fn evaluate_exposed(
+        &mut self,
+        shoe: &ShoeComposition,
+        hand: &Hand,
+        upcard: Card,
+        hole: Card,
+    ) -> ActionEv {
+        let mut dealer = single(upcard);
+        dealer.add_card(hole);
+        let showing = Showing {
+            total: Total::of(&dealer),
+            cards: 2,
+        };
+        self.evaluate_showing(shoe, hand, upcard, showing)
+    }
+
+    fn evaluate_showing(
+        &mut self,
+        shoe: &ShoeComposition,
+        hand: &Hand,
+        upcard: Card,
+        showing: Showing,
+    ) -> ActionEv {
+        let counts = shoe.counts();
+        let total = Total::of(hand);
+        let two_cards = hand.cards().len() == 2;
+        let natural_loss = self.natural_loss(1.0);
+        // The same limits `SplitHands` puts on a split hand at the table.
+        let split_aces = hand.is_split()
+            && hand.cards().first().is_some_and(|c| c.pip_value() == 1)
+            && !self.rules.hit_split_aces_allowed;
+        let can_double = two_cards
+            && !split_aces
+            && (!hand.is_split() || self.rules.double_after_split_allowed)
+            && self.rules.variant != Variant::Pontoon;
+
+        let stand = self.stand_ev(
+            counts,
+            total,
+            hand.is_blackjack(),
+            showing,
+            1.0,
+            natural_loss,
+        );
+        let hit = (!split_aces && total.value() < 21)
+            .then(|| self.hit_ev(counts, total, showing, natural_loss));
+        let double = (can_double && total.value() < 21)
+            .then(|| self.double_ev(counts, total, showing, self.natural_loss(2.0)));
+        let split = (hand.is_pair() && self.rules.split_limit > 0)
+            .then(|| self.split_ev(counts, hand.cards()[0].pip_value(), showing));
+        let surrender = (two_cards && !hand.is_split() && self.rules.surrender.allows_late())
+            .then(|| self.surrender_ev(counts, upcard, showing));
+
+        ActionEv {
+            stand,
+            hit,
+            double,
+            split,
+            surrender,
+        }
+    }
+
+    // What a hand staking `stake` loses to a dealer natural that was not peeked for.
+    fn natural_loss(&self, stake: f64) -> f64 {
+        match self.rules.hole_card {
+            HoleCardRule::Obo => 1.0,
+            _ => stake,
+        }
+    }
+
+    fn dealer_wins_ties(&self) -> bool {
+        matches!(
+            self.rules.variant,
+            Variant::DoubleExposure | Variant::Pontoon
+        )
+    }
+
+    fn dealer_from(&mut self, counts: [u16; 10], total: Total, cards: u8) -> [f64; 8] {
+        let value = total.value();
+        let mut finished = [0.0; 8];
+        if cards >= 2 {
+            if cards == 2 && value == 21 {
+                finished[NATURAL] = 1.0;
+                return finished;
+            }
+            if value > 21 {
+                finished[if value == 22 { TWENTY_TWO } else { OVER_22 }] = 1.0;
+                return finished;
+            }
+            let hits_soft_17 = value == 17 && total.is_soft() && self.rules.dealer_hits_soft_17;
+            if value >= 17 && !hits_soft_17 {
+                finished[(value - 17) as usize] = 1.0;
+                return finished;
+            }
+        }
+
+        let key = (counts, total, cards.min(3));
+        if let Some(&cached) = self.dealer.get(&key) {
+            return cached;
+        }
+
+        // A peeked hole card cannot be the one that completes a natural.
+        let excluded = match (cards, total.hard) {
+            (1, 1) if self.rules.dealer_has_hole_card() => Some(10),
+            (1, 10) if self.rules.dealer_has_hole_card() => Some(1),
+            _ => None,
+        };
+        let remaining: u32 = counts
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| Some(i as u8 + 1) != excluded)
+            .map(|(_, &c)| c as u32)
+            .sum();
+
+        let mut outcomes = [0.0; 8];
+        if remaining == 0 {
+            // An exhausted shoe never happens at a real table; the dealer just stops.
+            outcomes[0] = 1.0;
+            return outcomes;
+        }
+        for value in 1..=10u8 {
+            let count = counts[(value - 1) as usize];
+            if count == 0 || Some(value) == excluded {
+                continue;
+            }
+            let p = count as f64 / remaining as f64;
+            let next = self.dealer_from(draw(counts, value), total.add(value), cards + 1);
+            for (outcome, q) in outcomes.iter_mut().zip(next) {
+                *outcome += p * q;
+            }
+        }
+
+        self.dealer.insert(key, outcomes);
+        outcomes
+    }
+
+    fn stand_ev(
+        &mut self,
+        counts: [u16; 10],
+        total: Total,
+        natural: bool,
+        showing: Showing,
+        stake: f64,
+        natural_loss: f64,
+    ) -> f64 {
+        let dealer = self.dealer_from(counts, showing.total, showing.cards);
+        let player = total.value();
+        let spanish = self.rules.variant == Variant::Spanish21;
+        let ties_lose = self.dealer_wins_ties();
+
+        let mut ev = dealer[NATURAL]
+            * match natural {
+                true if ties_lose => -stake,
+                true if spanish => self.natural_ratio * stake,
+                true => 0.0,
+                false => -natural_loss,
+            };
+        let dealer_total = 1.0 - dealer[NATURAL];
+        if natural {
+            return ev + dealer_total * self.natural_ratio * stake;
+        }
+
+        ev += dealer[TWENTY_TWO]
+            * if self.rules.dealer_22_pushes() {
+                0.0
+            } else {
+                stake
+            };
+        ev += dealer[OVER_22] * stake;
+        for (i, &p) in dealer[..5].iter().enumerate() {
+            let dealer_value = 17 + i as u8;
+            let result = if spanish && player == 21 {
+                stake
+            } else {
+                match player.cmp(&dealer_value) {
+                    std::cmp::Ordering::Greater => stake,
+                    std::cmp::Ordering::Less => -stake,
+                    std::cmp::Ordering::Equal if ties_lose => -stake,
+                    std::cmp::Ordering::Equal => 0.0,
+                }
+            };
+            ev += p * result;
+        }
+        ev
+    }
+
+    // Best of standing and hitting on, for a hand that can no longer double or split.
+    fn best_ev(
+        &mut self,
+        counts: [u16; 10],
+        total: Total,
+        showing: Showing,
+        natural_loss: f64,
+    ) -> f64 {
+        let stand = self.stand_ev(counts, total, false, showing, 1.0, natural_loss);
+        if total.value() >= 21 {
+            return stand;
+        }
+
+        let key = (counts, total, showing, (natural_loss * 2.0) as u8);
+        if let Some(&cached) = self.player.get(&key) {
+            return cached;
+        }
+        let best = stand.max(self.hit_ev(counts, total, showing, natural_loss));
+        self.player.insert(key, best);
+        best
+    }
+
+    fn hit_ev(
+        &mut self,
+        counts: [u16; 10],
+        total: Total,
+        showing: Showing,
+        natural_loss: f64,
+    ) -> f64 {
+        self.each_card(counts, |calc, counts, value| {
+            let next = total.add(value);
+            if next.value() > 21 {
+                -1.0
+            } else {
+                calc.best_ev(counts, next, showing, natural_loss)
+            }
+        })
+    }
+
+    fn double_ev(
+        &mut self,
+        counts: [u16; 10],
+        total: Total,
+        showing: Showing,
+        natural_loss: f64,
+    ) -> f64 {
+        self.each_card(counts, |calc, counts, value| {
+            let next = total.add(value);
+            if next.value() > 21 {
+                -2.0
+            } else {
+                calc.stand_ev(counts, next, false, showing, 2.0, natural_loss)
+            }
+        })
+    }
+
+    // Both hands of a split pair are worth the same, so one is valued and doubled.
+    fn split_ev(&mut self, counts: [u16; 10], pair: u8, showing: Showing) -> f64 {
+        // Under OBO the pair risks one original bet between them to a dealer natural.
+        let share = if self.rules.hole_card == HoleCardRule::Obo {
+            0.5
+        } else {
+            1.0
+        };
+        let start = Total {
+            hard: pair,
+            ace: pair == 1,
+        };
+        let aces_stop = pair == 1 && !self.rules.hit_split_aces_allowed;
+        let das = self.rules.double_after_split_allowed;
+
+        let one_hand = self.each_card(counts, |calc, counts, value| {
+            let hand = start.add(value);
+            if aces_stop {
+                return calc.stand_ev(counts, hand, false, showing, 1.0, share);
+            }
+            let play = calc.best_ev(counts, hand, showing, share);
+            if das && hand.value() < 21 {
+                play.max(calc.double_ev(counts, hand, showing, share * 2.0))
+            } else {
+                play
+            }
+        });
+        2.0 * one_hand
+    }
+
+    fn surrender_ev(&mut self, counts: [u16; 10], upcard: Card, showing: Showing) -> f64 {
+        if self.rules.surrender.allows_early(upcard) || self.rules.dealer_has_hole_card() {
+            return -0.5;
+        }
+        // Late surrender without a hole card still loses everything to a natural.
+        let natural = self.dealer_from(counts, showing.total, showing.cards)[NATURAL];
+        -natural - 0.5 * (1.0 - natural)
+    }
+
+    fn each_card(
+        &mut self,
+        counts: [u16; 10],
+        mut value_of: impl FnMut(&mut Self, [u16; 10], u8) -> f64,
+    ) -> f64 {
+        let remaining: u32 = counts.iter().map(|&c| c as u32).sum();
+        if remaining == 0 {
+            return 0.0;
+        }
+        (1..=10u8)
+            .filter(|&v| counts[(v - 1) as usize] > 0)
+            .map(|v| {
+                let p = counts[(v - 1) as usize] as f64 / remaining as f64;
+                p * value_of(self, draw(counts, v), v)
+            })
+            .sum()
+    }
+}
+
+fn draw(mut counts: [u16; 10], value: u8) -> [u16; 10] {
+    counts[(value - 1) as usize] -= 1;
+    counts
+}
+
+fn single(card: Card) -> Hand {
+    let mut hand = Hand::new();
+    hand.add_card(card);
+    hand
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::card::{Rank, Suit};
+    use crate::core::rules::SurrenderRule;
+
+    fn card(rank: Rank) -> Card {
+        Card::new(rank, Suit::Spades)
+    }
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(card(rank));
+        }
+        hand
+    }
+
+    fn evaluate(calc: &mut EvCalculator, player: &[Rank], up: Rank) -> ActionEv {
+        let player = hand(player);
+        let shoe = ShoeComposition::from_rules(calc.rules())
+            .without(player.cards())
+            .without(&[card(up)]);
+        calc.evaluate(&shoe, &player, card(up))
+    }
+
+    #[test]
+    fn dealer_outcomes_sum_to_one() {
+        let rules = Rules::new();
+        let mut calc = EvCalculator::new(&rules);
+        for up in [Rank::Two, Rank::Six, Rank::Ten, Rank::Ace] {
+            let shoe = ShoeComposition::from_rules(&rules).without(&[card(up)]);
+            let o = calc.dealer_outcomes(&shoe, card(up));
+            let sum: f64 = (17..=21).map(|t| o.total(t)).sum::<f64>() + o.bust() + o.natural();
+            assert!((sum - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn dealer_six_busts_about_42_percent() {
+        let rules = Rules::new();
+        let mut calc = EvCalculator::new(&rules);
+        let shoe = ShoeComposition::from_rules(&rules).without(&[card(Rank::Six)]);
+        let bust = calc.dealer_outcomes(&shoe, card(Rank::Six)).bust();
+        assert!((bust - 0.42).abs() < 0.01, "{bust}");
+    }
+
+    #[test]
+    fn peek_removes_dealer_natural() {
+        let mut calc = EvCalculator::new(&Rules::new());
+        let shoe = ShoeComposition::from_rules(calc.rules()).without(&[card(Rank::Ace)]);
+        assert_eq!(calc.dealer_outcomes(&shoe, card(Rank::Ace)).natural(), 0.0);
+
+        let enhc = Rules {
+            hole_card: HoleCardRule::Enhc,
+            ..Rules::new()
+        };
+        let mut calc = EvCalculator::new(&enhc);
+        let natural = calc.dealer_outcomes(&shoe, card(Rank::Ace)).natural();
+        assert!((natural - 96.0 / 311.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn textbook_plays() {
+        let rules = Rules {
+            surrender: SurrenderRule::Late,
+            ..Rules::new()
+        };
+        let mut calc = EvCalculator::new(&rules);
+
+        let best = |calc: &mut EvCalculator, player: &[Rank], up: Rank| {
+            evaluate(calc, player, up).best().0
+        };
+        assert_eq!(
+            best(&mut calc, &[Rank::Six, Rank::Five], Rank::Six),
+            Action::Double
+        );
+        assert_eq!(
+            best(&mut calc, &[Rank::Ten, Rank::Two], Rank::Six),
+            Action::Stand
+        );
+        assert_eq!(
+            best(&mut calc, &[Rank::Ten, Rank::Two], Rank::Two),
+            Action::Hit
+        );
+        assert_eq!(
+            best(&mut calc, &[Rank::Ace, Rank::Ace], Rank::Nine),
+            Action::Split
+        );
+        assert_eq!(
+            best(&mut calc, &[Rank::Ten, Rank::Six], Rank::Ten),
+            Action::Surrender
+        );
+        assert_eq!(
+            best(&mut calc, &[Rank::Ten, Rank::King], Rank::Six),
+            Action::Stand
+        );
+    }
+
+    #[test]
+    fn published_values() {
+        let mut calc = EvCalculator::new(&Rules::new());
+        // six decks, S17: standing 20 against a 10 (no dealer natural) is worth about +0.555
+        let twenty = evaluate(&mut calc, &[Rank::Ten, Rank::Queen], Rank::Ten);
+        assert!((twenty.stand - 0.555).abs() < 0.01, "{}", twenty.stand);
+        // 16 against a 10 is close to -0.54 either way
+        let sixteen = evaluate(&mut calc, &[Rank::Ten, Rank::Six], Rank::Ten);
+        assert!((sixteen.stand - -0.54).abs() < 0.01, "{}", sixteen.stand);
+        assert!((sixteen.hit.unwrap() - -0.54).abs() < 0.01);
+        assert!(sixteen.surrender.is_none());
+    }
+
+    #[test]
+    fn natural_pays_the_table_ratio() {
+        let mut calc = EvCalculator::new(&Rules::new());
+        let natural = evaluate(&mut calc, &[Rank::Ace, Rank::King], Rank::Six);
+        assert!((natural.stand - 1.5).abs() < 1e-9);
+        assert!(natural.hit.is_none());
+
+        let mut switch = EvCalculator::new(&Rules::blackjack_switch());
+        let natural = evaluate(&mut switch, &[Rank::Ace, Rank::King], Rank::Six);
+        assert!((natural.stand - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn split_hands_double_only_where_the_table_lets_them() {
+        // an 8 split off and drawn to 11, and an ace split off and drawn to soft 12
+        let split = |pair: Rank, draw: Rank| {
+            let mut hand = hand(&[pair, pair]);
+            hand.split_off().unwrap();
+            hand.add_card(card(draw));
+            hand
+        };
+        let no_das = Rules {
+            double_after_split_allowed: false,
+            ..Rules::new()
+        };
+        let eleven = split(Rank::Eight, Rank::Three);
+        let shoe = ShoeComposition::from_rules(&no_das).without(&[card(Rank::Six)]);
+        let ev = EvCalculator::new(&no_das).evaluate(&shoe, &eleven, card(Rank::Six));
+        assert!(ev.double.is_none());
+        assert!(ev.hit.is_some());
+        let ev = EvCalculator::new(&Rules::new()).evaluate(&shoe, &eleven, card(Rank::Six));
+        assert!(ev.double.is_some());
+
+        let aces = split(Rank::Ace, Rank::Ace);
+        let ev = EvCalculator::new(&Rules::new()).evaluate(&shoe, &aces, card(Rank::Six));
+        assert!(ev.hit.is_none() && ev.double.is_none());
+        assert!(ev.split.is_some());
+    }
+
+    #[test]
+    fn double_exposure_plays_against_the_hole_card() {
+        let rules = Rules::double_exposure();
+        let mut calc = EvCalculator::new(&rules);
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        let against = |calc: &mut EvCalculator, hole: Rank| {
+            let shoe = ShoeComposition::from_rules(&rules)
+                .without(sixteen.cards())
+                .without(&[card(Rank::Ten), card(hole)]);
+            calc.evaluate_exposed(&shoe, &sixteen, card(Rank::Ten), card(hole))
+        };
+
+        // 16 stands on a dealer 16 but hits into a dealer 20 it cannot otherwise beat
+        assert_eq!(against(&mut calc, Rank::Six).best().0, Action::Stand);
+        assert_eq!(against(&mut calc, Rank::King).best().0, Action::Hit);
+        assert_eq!(against(&mut calc, Rank::King).stand, -1.0);
+    }
+
+    #[test]
+    fn regret_measures_mistakes() {
+        let mut calc = EvCalculator::new(&Rules::new());
+        let eleven = evaluate(&mut calc, &[Rank::Six, Rank::Five], Rank::Six);
+        assert_eq!(eleven.regret(&Action::Double), Some(0.0));
+        assert!(eleven.regret(&Action::Stand).unwrap() > 0.5);
+        assert_eq!(eleven.regret(&Action::Split), None);
+    }
+}
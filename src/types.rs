@@ -1,3 +1,4 @@
-mod action;
-mod phase;
-mod player;
+pub mod action;
+pub mod chat;
+pub mod phase;
+pub mod player;
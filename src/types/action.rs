@@ -1,3 +1,4 @@
+use crate::core::rules::Variant;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -5,18 +6,47 @@ use uuid::Uuid;
 pub enum Action {
     Leave,
     Spectate,
+    Sit, // take a seat back from spectating
+    Chat { text: String },
 
     Bet { amount: u32 },
-    BetInsurance { amount: u32 },
-    BetPerfectPairs { amount: u32 },
-    BetTwentyOnePlus3 { amount: u32 },
-    BetRoyalMatch { amount: u32 },
+    BetInsurance { amount: u32 },        // on each of the seat's boxes
+    EvenMoney,                           // on each box holding a natural
+    BetSide { id: String, amount: u32 }, // keyed by `SideBet::id`
 
     Hit,
     Stand,
     Double,
     Split,
     Surrender,
+    Switch, // Blackjack Switch: swap the second cards of the seat's two boxes
+    Buy,    // Pontoon: take a card and raise the stake by the original bet
+}
+
+impl Action {
+    /// The table's name for the action; Pontoon twists and sticks rather than hits and stands.
+    pub fn label(&self, variant: Variant) -> &'static str {
+        let pontoon = variant == Variant::Pontoon;
+        match self {
+            Action::Leave => "Leave",
+            Action::Spectate => "Spectate",
+            Action::Sit => "Sit",
+            Action::Chat { .. } => "Chat",
+            Action::Bet { .. } => "Bet",
+            Action::BetInsurance { .. } => "Insurance",
+            Action::EvenMoney => "Even money",
+            Action::BetSide { .. } => "Side bet",
+            Action::Hit if pontoon => "Twist",
+            Action::Hit => "Hit",
+            Action::Stand if pontoon => "Stick",
+            Action::Stand => "Stand",
+            Action::Double => "Double",
+            Action::Split => "Split",
+            Action::Surrender => "Surrender",
+            Action::Switch => "Switch",
+            Action::Buy => "Buy",
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -30,3 +60,10 @@ impl PlayerAction {
         Self { player_id, action }
     }
 }
+
+#[test]
+fn pontoon_labels() {
+    assert_eq!(Action::Hit.label(Variant::Pontoon), "Twist");
+    assert_eq!(Action::Stand.label(Variant::Pontoon), "Stick");
+    assert_eq!(Action::Hit.label(Variant::Classic), "Hit");
+}
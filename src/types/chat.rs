@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A line of table chat, from a seated player, a bot or a spectator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub player_id: Uuid,
+    pub name: String,
+    pub text: String,
+}
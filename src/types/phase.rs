@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Phase {
+    #[default]
     Betting,
     Dealing,
+    Switch,    // only entered in Blackjack Switch, before insurance and the peek
+    Insurance, // only entered when the dealer shows an Ace and insurance is offered
     PlayerTurns,
     DealerTurn,
     Payout,
@@ -15,6 +18,8 @@ impl Phase {
         match self {
             Phase::Betting => Phase::Dealing,
             Phase::Dealing => Phase::PlayerTurns,
+            Phase::Switch => Phase::PlayerTurns,
+            Phase::Insurance => Phase::PlayerTurns,
             Phase::PlayerTurns => Phase::DealerTurn,
             Phase::DealerTurn => Phase::Payout,
             Phase::Payout => Phase::RoundEnd,
@@ -23,12 +28,6 @@ impl Phase {
     }
 }
 
-impl Default for Phase {
-    fn default() -> Self {
-        Phase::Betting
-    }
-}
-
 #[test]
 fn phase_progression() {
     let mut phase = Phase::Betting;
@@ -51,3 +50,13 @@ fn phase_progression() {
     phase = phase.next();
     assert_eq!(phase, Phase::Betting);
 }
+
+#[test]
+fn insurance_rejoins_player_turns() {
+    assert_eq!(Phase::Insurance.next(), Phase::PlayerTurns);
+}
+
+#[test]
+fn switch_rejoins_player_turns() {
+    assert_eq!(Phase::Switch.next(), Phase::PlayerTurns);
+}
@@ -1,5 +1,6 @@
-use crate::core::hand::Hand;
+use crate::core::split::SplitHands;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -15,13 +16,12 @@ pub struct Player {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlayerHand {
     pub player_id: Uuid,
-    pub hand: Hand,
+    pub hands: SplitHands,
     pub bet: u32,
     pub insurance_bet: u32,
-    pub perfect_pairs_bet: u32,
-    pub twenty_one_plus_3_bet: u32,
-    pub royal_match_bet: u32,
-    // more side bets coming soon
+    pub even_money: bool,
+    pub side_bets: BTreeMap<String, u32>, // wagers keyed by side bet id
+    pub switched: bool,                   // Blackjack Switch: this box has traded cards
 }
 
 impl Player {
@@ -41,12 +41,17 @@ impl PlayerHand {
     pub fn new(player_id: Uuid, bet: u32) -> Self {
         Self {
             player_id,
-            hand: Hand::new(),
+            hands: SplitHands::new(bet),
             bet,
             insurance_bet: 0,
-            perfect_pairs_bet: 0,
-            twenty_one_plus_3_bet: 0,
-            royal_match_bet: 0,
+            even_money: false,
+            side_bets: BTreeMap::new(),
+            switched: false,
         }
     }
+
+    /// House chips riding on this seat's hands from Free Bet doubles and splits.
+    pub fn free_chips(&self) -> u64 {
+        self.hands.free_chips()
+    }
 }